@@ -0,0 +1,93 @@
+use std::ptr::NonNull;
+
+use rkyv::{de::Pool, rancor::Strategy, Archive, Archived, Deserialize, Serialize};
+
+use super::{gen_level::LevelGenerator, DefaultGenerator, KeyError, SkipList, MAX_LEVEL};
+
+/// Archivable form of a list: only the ordered pairs, without the towers.
+#[derive(Archive, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SortedPairs<K, V> {
+    pub pairs: Vec<(K, V)>,
+}
+
+impl<K, V, G> From<SkipList<K, V, G>> for SortedPairs<K, V>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    fn from(sl: SkipList<K, V, G>) -> Self {
+        let mut pairs = Vec::with_capacity(sl.len());
+        pairs.extend(sl);
+        SortedPairs { pairs }
+    }
+}
+
+impl<K, V> SkipList<K, V, DefaultGenerator>
+where
+    K: Ord + Archive,
+    V: Archive,
+{
+    /// Rebuilds a list from archived pairs, appending them one by one as in `from_sorted`.
+    /// The archive may be untrusted, so keys out of strict order fail with
+    /// `KeyError::Unsorted`.
+    pub fn from_archived<E>(archived: &Archived<SortedPairs<K, V>>) -> Result<Self, E>
+    where
+        Archived<(K, V)>: Deserialize<(K, V), Strategy<Pool, E>>,
+        E: rkyv::rancor::Source,
+    {
+        let mut sl = Self::new();
+        let mut tails = [Some(NonNull::from(&sl.head)); MAX_LEVEL];
+        for pair in archived.pairs.iter() {
+            let (key, value) = rkyv::deserialize::<(K, V), E>(pair)?;
+
+            let last_ptr = tails[0].expect("must have a tail node.");
+            if unsafe { last_ptr.as_ref().key() }.is_some_and(|k| *k >= key) {
+                return Err(E::new(KeyError::Unsorted));
+            }
+
+            sl.push_back(&mut tails, key, value);
+        }
+
+        Ok(sl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::rancor::Error;
+
+    use super::SortedPairs;
+    use crate::collections::skip_list::SkipList;
+
+    #[test]
+    fn archive_round_trip() {
+        let sl = SkipList::from_sorted((0..100u32).map(|i| (i, i.to_string())));
+
+        let bytes = rkyv::to_bytes::<Error>(&SortedPairs::from(sl)).unwrap();
+        let archived =
+            rkyv::access::<rkyv::Archived<SortedPairs<u32, String>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.pairs.len(), 100);
+        assert_eq!(archived.pairs[42].1, "42");
+
+        let sl = SkipList::from_archived::<Error>(archived).unwrap();
+        assert_eq!(sl.len(), 100);
+        for i in 0..100 {
+            assert_eq!(sl.get(&i), Some(&i.to_string()));
+        }
+    }
+
+    #[test]
+    fn from_archived_unsorted() {
+        for keys in [vec![1u32, 3, 2], vec![1, 2, 2]] {
+            let pairs = SortedPairs {
+                pairs: keys.into_iter().map(|k| (k, k)).collect(),
+            };
+
+            let bytes = rkyv::to_bytes::<Error>(&pairs).unwrap();
+            let archived =
+                rkyv::access::<rkyv::Archived<SortedPairs<u32, u32>>, Error>(&bytes).unwrap();
+            let err = SkipList::from_archived::<Error>(archived).err().unwrap();
+            assert_eq!(err.to_string(), "keys are not in strictly increasing order");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::ptr::NonNull;
+
+use super::{gen_level::LevelGenerator, AdjustNodes, DefaultGenerator, Node, SkipList};
+
+/// A position in the list that can edit in place. It sits in front of a node, or at the end,
+/// and keeps the last node before it on every level, so removing there needs no search.
+pub struct CursorMut<'a, K, V, G = DefaultGenerator>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    list: &'a mut SkipList<K, V, G>,
+    adjust_nodes: AdjustNodes<K, V>,
+}
+
+impl<K, V, G> SkipList<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    /// Removes `key` like `remove`, and returns a cursor at the entry that followed it, or
+    /// where it would be if absent. Both come from a single search.
+    pub fn remove_and_cursor(&mut self, key: &K) -> (Option<(K, V)>, CursorMut<'_, K, V, G>) {
+        let (_, adjust_nodes) = Self::get_adjust_nodes(&self.head, key);
+        let removed = self
+            .remove_after_if(&adjust_nodes, key, |_, _| true)
+            .map(|data| data.into());
+
+        (
+            removed,
+            CursorMut {
+                list: self,
+                adjust_nodes,
+            },
+        )
+    }
+}
+
+impl<K, V, G> CursorMut<'_, K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    fn current_ptr(&self) -> Option<NonNull<Node<K, V>>> {
+        self.adjust_nodes[0].and_then(|ptr| unsafe { ptr.as_ref().next_node_ptr() })
+    }
+
+    /// The entry under the cursor, `None` at the end.
+    pub fn current(&self) -> Option<(&K, &V)> {
+        self.current_ptr()
+            .and_then(|ptr| unsafe { &*ptr.as_ptr() }.key_value())
+    }
+
+    pub fn key(&self) -> Option<&K> {
+        self.current().map(|(k, _)| k)
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.current_ptr()
+            .and_then(|ptr| unsafe { &mut *ptr.as_ptr() }.value_mut())
+    }
+
+    /// Steps to the next entry. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if let Some(ptr) = self.current_ptr() {
+            let level = unsafe { ptr.as_ref().level() };
+            for adjust_node in self.adjust_nodes.iter_mut().take(level) {
+                *adjust_node = Some(ptr);
+            }
+        }
+    }
+
+    /// Removes the entry under the cursor, which moves on to the one after it.
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        self.current_ptr()?;
+        Some(self.list.remove_after(&self.adjust_nodes).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::skip_list::SkipList;
+
+    #[test]
+    fn remove_and_cursor() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            sl.insert(i, i);
+        }
+
+        let (removed, mut cursor) = sl.remove_and_cursor(&3);
+        assert_eq!(removed, Some((3, 3)));
+        assert_eq!(cursor.current(), Some((&4, &4)));
+
+        *cursor.value_mut().unwrap() = 40;
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some((5, 5)));
+        assert_eq!(cursor.remove_current(), Some((6, 6)));
+        assert_eq!(cursor.key(), Some(&7));
+
+        assert_eq!(sl.len(), 7);
+        assert_eq!(
+            sl.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 2), (4, 40), (7, 7), (8, 8), (9, 9)]
+        );
+
+        let (removed, mut cursor) = sl.remove_and_cursor(&100);
+        assert_eq!(removed, None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+
+        let (removed, cursor) = sl.remove_and_cursor(&3);
+        assert_eq!(removed, None);
+        assert_eq!(cursor.key(), Some(&4));
+    }
+}
@@ -0,0 +1,570 @@
+use std::{borrow::Borrow, ops::AddAssign, ptr::NonNull};
+
+use super::{gen_level::LevelGenerator, AdjustNodes, DefaultGenerator, Node, SkipList};
+
+pub enum Entry<'a, K, V, G = DefaultGenerator>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    Occupied(OccupiedEntry<'a, K, V, G>),
+    Vacant(VacantEntry<'a, K, V, G>),
+}
+
+/// Like `Entry`, but the key is only turned into an owned `K` when a vacant entry is filled.
+pub enum EntryRef<'a, K, V, Q, G = DefaultGenerator>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+    G: LevelGenerator,
+{
+    Occupied(OccupiedEntry<'a, K, V, G>),
+    Vacant(VacantEntryRef<'a, K, V, Q, G>),
+}
+
+pub struct OccupiedEntry<'a, K, V, G = DefaultGenerator>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    list: &'a mut SkipList<K, V, G>,
+    node: NonNull<Node<K, V>>,
+    adjust_nodes: AdjustNodes<K, V>,
+}
+
+pub struct VacantEntry<'a, K, V, G = DefaultGenerator>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    list: &'a mut SkipList<K, V, G>,
+    key: K,
+    adjust_nodes: AdjustNodes<K, V>,
+}
+
+pub struct VacantEntryRef<'a, K, V, Q, G = DefaultGenerator>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+    G: LevelGenerator,
+{
+    list: &'a mut SkipList<K, V, G>,
+    key: &'a Q,
+    adjust_nodes: AdjustNodes<K, V>,
+}
+
+impl<K, V, G> SkipList<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, G> {
+        let (cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+
+        match unsafe { cur_ptr.as_ref().next_node_ptr() } {
+            Some(node) if unsafe { node.as_ref().key() } == Some(&key) => {
+                Entry::Occupied(OccupiedEntry {
+                    list: self,
+                    node,
+                    adjust_nodes,
+                })
+            }
+            _ => Entry::Vacant(VacantEntry {
+                list: self,
+                key,
+                adjust_nodes,
+            }),
+        }
+    }
+
+    /// An occupied entry for the entry at `index` in key order, `None` if `index >= len`.
+    /// Nodes don't record spans, so finding it walks level 0 in O(index).
+    pub fn index_entry_mut(&mut self, index: usize) -> Option<OccupiedEntry<'_, K, V, G>> {
+        if index >= self.len {
+            return None;
+        }
+
+        let (cur_ptr, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, index);
+        let node = unsafe { cur_ptr.as_ref().next_node_ptr() }?;
+        Some(OccupiedEntry {
+            list: self,
+            node,
+            adjust_nodes,
+        })
+    }
+
+    /// `entry` for `Copy` keys. Never clones `key` and descends once, the same as `entry`.
+    pub fn entry_copy(&mut self, key: K) -> Entry<'_, K, V, G>
+    where
+        K: Copy,
+    {
+        self.entry(key)
+    }
+
+    /// Applies `modify` to the value for `key`, or inserts `init()` if there is none, with a
+    /// single search.
+    pub fn upsert<FI, FM>(&mut self, key: K, init: FI, modify: FM) -> &mut V
+    where
+        FI: FnOnce() -> V,
+        FM: FnOnce(&mut V),
+    {
+        match self.entry(key) {
+            Entry::Occupied(entry) => {
+                let value = entry.into_mut();
+                modify(value);
+                value
+            }
+            Entry::Vacant(entry) => entry.insert(init()),
+        }
+    }
+
+    /// Like `insert`, but also returns a reference to the value now stored for `key`, with a
+    /// single search.
+    pub fn insert_or_get(&mut self, key: K, value: V) -> (&mut V, Option<V>) {
+        match self.entry(key) {
+            Entry::Occupied(entry) => {
+                let current = entry.into_mut();
+                let old = std::mem::replace(current, value);
+                (current, Some(old))
+            }
+            Entry::Vacant(entry) => (entry.insert(value), None),
+        }
+    }
+
+    /// Adds `delta` to the value for `key`, inserting `delta` if there is none, with a single
+    /// search. Returns the new value.
+    pub fn add(&mut self, key: K, delta: V) -> &V
+    where
+        V: AddAssign,
+    {
+        match self.entry(key) {
+            Entry::Occupied(entry) => {
+                let value = entry.into_mut();
+                *value += delta;
+                value
+            }
+            Entry::Vacant(entry) => entry.insert(delta),
+        }
+    }
+
+    /// Counts one more `key`, like `add(key, 1)`. Returns the new count.
+    pub fn increment(&mut self, key: K) -> &V
+    where
+        V: AddAssign + From<u8>,
+    {
+        self.add(key, V::from(1))
+    }
+
+    /// Shorthand for `entry(key).or_default()`.
+    pub fn get_or_insert_default(&mut self, key: K) -> &mut V
+    where
+        V: Default,
+    {
+        self.entry(key).or_default()
+    }
+
+    pub fn entry_ref<'a, Q>(&'a mut self, key: &'a Q) -> EntryRef<'a, K, V, Q, G>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K> + ?Sized,
+    {
+        let (cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, key);
+
+        match unsafe { cur_ptr.as_ref().next_node_ptr() } {
+            Some(node) if unsafe { node.as_ref().key() }.is_some_and(|k| k.borrow() == key) => {
+                EntryRef::Occupied(OccupiedEntry {
+                    list: self,
+                    node,
+                    adjust_nodes,
+                })
+            }
+            _ => EntryRef::Vacant(VacantEntryRef {
+                list: self,
+                key,
+                adjust_nodes,
+            }),
+        }
+    }
+}
+
+impl<'a, K, V, G> Entry<'a, K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, Q, G> EntryRef<'a, K, V, Q, G>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+    G: LevelGenerator,
+{
+    pub fn key(&self) -> &Q {
+        match self {
+            EntryRef::Occupied(entry) => entry.key().borrow(),
+            EntryRef::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => entry.insert(default()),
+        }
+    }
+}
+
+impl<'a, K, V, G> OccupiedEntry<'a, K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    pub fn key(&self) -> &K {
+        unsafe { self.node.as_ref() }
+            .key()
+            .expect("must have data.")
+    }
+
+    /// Removes the entry and returns its value. Unlinks through the predecessors found by
+    /// `entry`, without searching again.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Like `remove`, but also returns the key.
+    pub fn remove_entry(self) -> (K, V) {
+        self.list.remove_after(&self.adjust_nodes).into()
+    }
+
+    pub fn get(&self) -> &V {
+        unsafe { self.node.as_ref() }
+            .value()
+            .expect("must have data.")
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { self.node.as_mut() }
+            .value_mut()
+            .expect("must have data.")
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut *self.node.as_ptr() }
+            .value_mut()
+            .expect("must have data.")
+    }
+}
+
+impl<'a, K, V, G> VacantEntry<'a, K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Splices a node holding `value` in at the position found by `entry`, with a fresh
+    /// random level, and returns a reference to the value.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let node = self.list.insert_at(self.adjust_nodes, self.key, value);
+        unsafe { &mut *node.as_ptr() }
+            .value_mut()
+            .expect("must have data.")
+    }
+}
+
+impl<'a, K, V, Q, G> VacantEntryRef<'a, K, V, Q, G>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+    G: LevelGenerator,
+{
+    pub fn key(&self) -> &Q {
+        self.key
+    }
+
+    /// Like `VacantEntry::insert`, turning the key into an owned `K` only now.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let node = self
+            .list
+            .insert_at(self.adjust_nodes, self.key.to_owned(), value);
+        unsafe { &mut *node.as_ptr() }
+            .value_mut()
+            .expect("must have data.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::skip_list::{Entry, EntryRef, SkipList};
+
+    #[test]
+    fn entry() {
+        let mut sl = SkipList::new();
+
+        *sl.entry(1).or_insert(0) += 1;
+        *sl.entry(1).or_insert(0) += 1;
+        *sl.entry(2).or_insert_with(|| 10) += 1;
+
+        assert_eq!(sl.get(&1), Some(&2));
+        assert_eq!(sl.get(&2), Some(&11));
+        assert_eq!(sl.len(), 2);
+    }
+
+    #[test]
+    fn or_default() {
+        let mut sl = SkipList::new();
+
+        for i in [3, 1, 3, 3, 2] {
+            *sl.entry(i).or_default() += 1;
+        }
+
+        assert_eq!(
+            sl.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn upsert() {
+        let mut sl = SkipList::new();
+
+        assert_eq!(*sl.upsert(1, || vec![0], |v| v.push(1)), vec![0]);
+        assert_eq!(*sl.upsert(1, || vec![0], |v| v.push(1)), vec![0, 1]);
+        sl.upsert(1, || unreachable!(), |v| v.push(2));
+
+        assert_eq!(sl.get(&1), Some(&vec![0, 1, 2]));
+        assert_eq!(sl.len(), 1);
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let mut sl: SkipList<String, usize> = SkipList::new();
+
+        for word in ["b", "a", "b"] {
+            *sl.entry_ref(word).or_insert(0) += 1;
+        }
+        *sl.get_mut("a").unwrap() += 10;
+
+        assert_eq!(sl.get("a"), Some(&11));
+        assert_eq!(sl.get("b"), Some(&2));
+        assert_eq!(sl.get("c"), None);
+        assert!(matches!(sl.entry_ref("b"), EntryRef::Occupied(_)));
+    }
+
+    #[test]
+    fn entry_key() {
+        let mut sl = SkipList::new();
+        sl.insert(1, 10);
+
+        assert_eq!(sl.entry(1).key(), &1);
+        assert_eq!(sl.entry(2).key(), &2);
+        match sl.entry(1) {
+            Entry::Occupied(entry) => assert_eq!(entry.key(), &1),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match sl.entry(2) {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(entry) => assert_eq!(entry.key(), &2),
+        }
+
+        let mut sl: SkipList<String, usize> = SkipList::new();
+        sl.insert("a".to_string(), 1);
+        assert_eq!(sl.entry_ref("a").key(), "a");
+        assert_eq!(sl.entry_ref("b").key(), "b");
+        assert_eq!(sl.len(), 1);
+    }
+
+    #[test]
+    fn occupied_insert() {
+        let mut sl = SkipList::new();
+        sl.insert(1, 10);
+
+        let Entry::Occupied(mut entry) = sl.entry(1) else {
+            unreachable!()
+        };
+        assert_eq!(entry.get(), &10);
+        *entry.get_mut() += 1;
+        assert_eq!(entry.insert(20), 11);
+        *entry.into_mut() += 1;
+
+        assert_eq!(sl.get(&1), Some(&21));
+        assert_eq!(sl.len(), 1);
+    }
+
+    #[test]
+    fn vacant_insert() {
+        let mut sl = SkipList::new();
+        for i in (0..100).step_by(2) {
+            sl.insert(i, i);
+        }
+
+        for i in (1..100).step_by(2) {
+            let Entry::Vacant(entry) = sl.entry(i) else {
+                unreachable!()
+            };
+            *entry.insert(i) *= 10;
+        }
+
+        assert_eq!(sl.len(), 100);
+        assert_eq!(sl.get(&3), Some(&30));
+        assert!(sl.iter().map(|(k, _)| *k).eq(0..100));
+
+        let mut sl: SkipList<String, usize> = SkipList::new();
+        let EntryRef::Vacant(entry) = sl.entry_ref("a") else {
+            unreachable!()
+        };
+        assert_eq!(*entry.insert(1), 1);
+        assert_eq!(sl.get(&"a".to_string()), Some(&1));
+    }
+
+    #[test]
+    fn occupied_remove() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i, i * 10);
+        }
+
+        for i in (0..100).step_by(2) {
+            match sl.entry(i) {
+                Entry::Occupied(entry) if i % 4 == 0 => assert_eq!(entry.remove(), i * 10),
+                Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), (i, i * 10)),
+                Entry::Vacant(_) => unreachable!(),
+            }
+        }
+
+        assert_eq!(sl.len(), 50);
+        assert!(sl.iter().map(|(k, _)| *k).eq((1..100).step_by(2)));
+        assert_eq!(sl.get(&2), None);
+        sl.insert(2, 0);
+        assert_eq!(sl.get(&2), Some(&0));
+    }
+
+    #[test]
+    fn index_entry_mut() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            sl.insert(i, i * 10);
+        }
+
+        let mut entry = sl.index_entry_mut(3).unwrap();
+        assert_eq!(entry.key(), &3);
+        *entry.get_mut() += 1;
+        assert_eq!(sl.get(&3), Some(&31));
+
+        assert_eq!(sl.index_entry_mut(0).unwrap().remove_entry(), (0, 0));
+        assert_eq!(sl.index_entry_mut(8).unwrap().remove(), 90);
+        assert!(sl.index_entry_mut(8).is_none());
+        assert_eq!(sl.len(), 8);
+        assert!(sl.iter().map(|(k, _)| *k).eq(1..9));
+    }
+
+    #[test]
+    fn entry_copy() {
+        let mut sl = SkipList::new();
+
+        *sl.entry_copy(1).or_insert(0) += 1;
+        *sl.entry_copy(1).or_insert(0) += 1;
+        assert!(matches!(sl.entry_copy(2), Entry::Vacant(_)));
+
+        assert_eq!(sl.get(&1), Some(&2));
+        assert_eq!(sl.len(), 1);
+    }
+
+    #[test]
+    fn insert_or_get() {
+        let mut sl = SkipList::new();
+
+        let (value, old) = sl.insert_or_get(1, 10);
+        assert_eq!((*value, old), (10, None));
+        *value += 1;
+
+        let (value, old) = sl.insert_or_get(1, 20);
+        assert_eq!((*value, old), (20, Some(11)));
+
+        assert_eq!(sl.get(&1), Some(&20));
+        assert_eq!(sl.len(), 1);
+    }
+
+    #[test]
+    fn increment_and_add() {
+        let mut sl: SkipList<&str, u64> = SkipList::new();
+
+        assert_eq!(*sl.increment("a"), 1);
+        assert_eq!(*sl.increment("a"), 2);
+        assert_eq!(*sl.add("b", 5), 5);
+        assert_eq!(*sl.add("a", 10), 12);
+
+        assert_eq!(sl.get(&"a"), Some(&12));
+        assert_eq!(sl.get(&"b"), Some(&5));
+        assert_eq!(sl.len(), 2);
+    }
+
+    #[test]
+    fn get_or_insert_default() {
+        let mut sl = SkipList::new();
+
+        for key in [3, 1, 3, 2, 3] {
+            *sl.get_or_insert_default(key) += 1;
+        }
+
+        assert_eq!(
+            sl.iter().collect::<Vec<_>>(),
+            vec![(&1, &1), (&2, &1), (&3, &3)]
+        );
+    }
+
+    #[test]
+    fn entry_ref() {
+        let mut sl: SkipList<String, usize> = SkipList::new();
+
+        for word in ["b", "a", "b", "c", "b"] {
+            *sl.entry_ref(word).or_insert(0) += 1;
+        }
+
+        assert_eq!(sl.len(), 3);
+        assert_eq!(
+            sl.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+            vec![("a", 1), ("b", 3), ("c", 1)]
+        );
+
+        assert!(matches!(sl.entry_ref("a"), EntryRef::Occupied(_)));
+        assert!(matches!(sl.entry_ref("d"), EntryRef::Vacant(_)));
+        assert_eq!(sl.len(), 3);
+    }
+}
@@ -0,0 +1,69 @@
+use std::{borrow::Borrow, ptr::NonNull};
+
+use super::{AdjustNodes, Node, MAX_LEVEL};
+
+/// The nodes a search stopped at on each level, kept so the next search for a greater key
+/// starts from there instead of from the head. A smaller key restarts from the head.
+pub(crate) struct Finger<K, V> {
+    head: NonNull<Node<K, V>>,
+    pub(crate) adjust_nodes: AdjustNodes<K, V>,
+}
+
+impl<K, V> Finger<K, V>
+where
+    K: Ord,
+{
+    pub(crate) fn new(head: &Node<K, V>) -> Self {
+        Self {
+            head: NonNull::from(head),
+            adjust_nodes: [None; MAX_LEVEL],
+        }
+    }
+
+    /// Moves the finger to `key` and returns the last node before it.
+    pub(crate) fn seek<Q>(&mut self, key: &Q) -> NonNull<Node<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let before_key = |node_ptr: NonNull<Node<K, V>>| {
+            unsafe { node_ptr.as_ref().key() }.is_some_and(|k| k.borrow() < key)
+        };
+
+        let height = unsafe { self.head.as_ref().level() };
+        if height == 0 {
+            return self.head;
+        }
+
+        let reset = self.adjust_nodes[0].is_none_or(|ptr| ptr != self.head && !before_key(ptr));
+        for adjust_node in self.adjust_nodes.iter_mut().take(height) {
+            if reset || adjust_node.is_none() {
+                *adjust_node = Some(self.head);
+            }
+        }
+
+        let forward_before_key = |adjust_nodes: &AdjustNodes<K, V>, level: usize| {
+            let node_ptr = adjust_nodes[level].expect("must have an adjust node.");
+            unsafe { node_ptr.as_ref().forward[level] }.is_some_and(before_key)
+        };
+
+        let mut top_level = 0;
+        while top_level + 1 < height && forward_before_key(&self.adjust_nodes, top_level + 1) {
+            top_level += 1;
+        }
+
+        let mut cur_ptr = self.adjust_nodes[top_level].expect("must have an adjust node.");
+        for level in (0..=top_level).rev() {
+            unsafe {
+                while let Some(next_ptr) =
+                    cur_ptr.as_ref().forward[level].filter(|&ptr| before_key(ptr))
+                {
+                    cur_ptr = next_ptr;
+                }
+            }
+            self.adjust_nodes[level] = Some(cur_ptr);
+        }
+
+        cur_ptr
+    }
+}
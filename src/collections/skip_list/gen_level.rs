@@ -1,5 +1,9 @@
+use rand::Rng;
+
 use crate::collections::skip_list::MAX_LEVEL;
 
+const P: f64 = 0.6;
+
 pub trait LevelGenerator {
     /// requires: 0 < result <= 32
     fn random_level(&mut self) -> usize;
@@ -10,33 +14,56 @@ pub struct DefaultGenerator;
 
 impl LevelGenerator for DefaultGenerator {
     fn random_level(&mut self) -> usize {
-        const P: f64 = 0.6;
-        let mut level = 1;
-        let mut x = P;
-
-        let f = 1. - rand::random::<f64>();
-        while x > f && level < MAX_LEVEL {
-            level += 1;
-            x *= P;
-        }
+        random_level_with(&mut rand::thread_rng())
+    }
+}
+
+/// A level drawn from `rng` with the same distribution as `DefaultGenerator`.
+pub(crate) fn random_level_with<R: Rng>(rng: &mut R) -> usize {
+    let mut level = 1;
+    let mut x = P;
+
+    let f = 1. - rng.gen::<f64>();
+    while x > f && level < MAX_LEVEL {
+        level += 1;
+        x *= P;
+    }
+
+    level
+}
 
-        level
+/// The top level `len` entries are expected to reach with `DefaultGenerator`, `ceil(log_{1/P}(len))`.
+pub(crate) fn expected_level(len: usize) -> usize {
+    if len <= 1 {
+        return 1;
     }
+
+    let level = ((len as f64).ln() / (1. / P).ln()).ceil() as usize;
+    level.clamp(1, MAX_LEVEL)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::collections::skip_list::{LevelGenerator, MAX_LEVEL};
 
-    use super::DefaultGenerator;
+    use super::{expected_level, DefaultGenerator};
 
     #[test]
     fn default_generator() {
-        let mut gen = DefaultGenerator::default();
+        let mut gen = DefaultGenerator;
 
         for _ in 0..1000000 {
             let level = gen.random_level();
             assert!(0 < level && level <= MAX_LEVEL);
         }
     }
+
+    #[test]
+    fn expected_level_bounds() {
+        assert_eq!(expected_level(0), 1);
+        assert_eq!(expected_level(1), 1);
+        assert_eq!(expected_level(2), 2);
+        assert!(expected_level(1000) < expected_level(1000000));
+        assert_eq!(expected_level(usize::MAX), MAX_LEVEL);
+    }
 }
@@ -1,3 +1,5 @@
+use std::{cmp::Reverse, collections::BinaryHeap, iter::Peekable, ops::Bound, ptr::NonNull};
+
 use super::{gen_level::LevelGenerator, Node, SkipList};
 
 pub struct Iter<'a, K, V>
@@ -6,6 +8,11 @@ where
     V: 'a,
 {
     current: Option<&'a Node<K, V>>,
+    #[cfg(feature = "doubly-linked")]
+    head: &'a Node<K, V>,
+    /// The next entry from the back, or `None` until `next_back` first looks up the tail.
+    #[cfg(feature = "doubly-linked")]
+    back: Option<Option<&'a Node<K, V>>>,
     _marker: std::marker::PhantomData<&'a Node<K, V>>,
 }
 
@@ -24,6 +31,16 @@ where
                     None => None,
                 };
 
+                #[cfg(feature = "doubly-linked")]
+                if self
+                    .back
+                    .flatten()
+                    .is_some_and(|back| std::ptr::eq(back, node))
+                {
+                    self.current = None;
+                    self.back = Some(None);
+                }
+
                 let result = node
                     .data
                     .as_ref()
@@ -37,8 +54,68 @@ where
     }
 }
 
+#[cfg(feature = "doubly-linked")]
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let back = match self.back {
+            Some(back) => back,
+            None => self.current.and_then(|_| {
+                let (last_ptr, _) = SkipList::<K, V>::get_tail_nodes(self.head);
+                Some(unsafe { &*last_ptr.as_ptr() }).filter(|node| node.data.is_some())
+            }),
+        };
+        self.back = Some(None);
+        let node = back?;
+
+        if self
+            .current
+            .is_some_and(|current| std::ptr::eq(current, node))
+        {
+            self.current = None;
+        } else {
+            self.back = Some(node.prev.map(|prev| unsafe { &*prev.as_ptr() }));
+        }
+
+        let result = node
+            .data
+            .as_ref()
+            .map(|data| (&data.key, &data.value))
+            .expect("must have data.");
+
+        Some(result)
+    }
+}
+
+/// `Iter` over a whole list, which knows how many entries are left.
+struct ExactIter<'a, K, V> {
+    inner: Iter<'a, K, V>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for ExactIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for ExactIter<'_, K, V> {}
+
 pub struct IterMut<'a, K: 'a, V: 'a> {
-    current: Option<&'a mut Node<K, V>>,
+    current: Option<NonNull<Node<K, V>>>,
+    #[cfg(feature = "doubly-linked")]
+    back: Option<NonNull<Node<K, V>>>,
     _marker: std::marker::PhantomData<&'a mut Node<K, V>>,
 }
 
@@ -46,20 +123,144 @@ impl<'a, K: 'a, V: 'a> Iterator for IterMut<'a, K, V> {
     type Item = (&'a K, &'a mut V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.current.take() {
-            Some(node) => {
-                self.current = node.next.as_mut().map(|node| node.as_mut());
+        let node_ptr = self.current.take()?;
+        let node = unsafe { &mut *node_ptr.as_ptr() };
+
+        // Where the ends meet, stop before touching nodes the back already handed out.
+        #[cfg(feature = "doubly-linked")]
+        if self.back == Some(node_ptr) {
+            self.back = None;
+        } else {
+            self.current = node.next.as_deref_mut().map(NonNull::from);
+        }
+        #[cfg(not(feature = "doubly-linked"))]
+        {
+            self.current = node.next.as_deref_mut().map(NonNull::from);
+        }
 
-                let result = node
-                    .data
-                    .as_mut()
-                    .map(|data| (&data.key, &mut data.value))
-                    .expect("must have data.");
+        let result = node
+            .data
+            .as_mut()
+            .map(|data| (&data.key, &mut data.value))
+            .expect("must have data.");
 
-                Some(result)
+        Some(result)
+    }
+}
+
+#[cfg(feature = "doubly-linked")]
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node_ptr = self.back.take()?;
+        let node = unsafe { &mut *node_ptr.as_ptr() };
+
+        if self.current == Some(node_ptr) {
+            self.current = None;
+        } else {
+            self.back = node.prev;
+        }
+
+        let result = node
+            .data
+            .as_mut()
+            .map(|data| (&data.key, &mut data.value))
+            .expect("must have data.");
+
+        Some(result)
+    }
+}
+
+/// Full outer join of two lists by key, see `SkipList::merge_join`.
+pub struct MergeJoin<'a, K, V, W>
+where
+    K: 'a,
+    V: 'a,
+    W: 'a,
+{
+    left: Peekable<Iter<'a, K, V>>,
+    right: Peekable<Iter<'a, K, W>>,
+}
+
+impl<'a, K, V, W> Iterator for MergeJoin<'a, K, V, W>
+where
+    K: Ord + 'a,
+    V: 'a,
+    W: 'a,
+{
+    type Item = (&'a K, Option<&'a V>, Option<&'a W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = match (self.left.peek(), self.right.peek()) {
+            (Some((left_key, _)), Some((right_key, _))) => left_key.cmp(right_key),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => return None,
+        };
+
+        match order {
+            std::cmp::Ordering::Less => {
+                let (key, value) = self.left.next()?;
+                Some((key, Some(value), None))
             }
-            None => None,
+            std::cmp::Ordering::Greater => {
+                let (key, value) = self.right.next()?;
+                Some((key, None, Some(value)))
+            }
+            std::cmp::Ordering::Equal => {
+                let (key, left_value) = self.left.next()?;
+                let (_, right_value) = self.right.next()?;
+                Some((key, Some(left_value), Some(right_value)))
+            }
+        }
+    }
+}
+
+/// K-way union of several lists by key, see `SkipList::merge_iter`.
+pub struct MergeIter<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    iters: Vec<Iter<'a, K, V>>,
+    values: Vec<Option<&'a V>>,
+    heap: BinaryHeap<Reverse<(&'a K, usize)>>,
+}
+
+impl<'a, K, V> MergeIter<'a, K, V>
+where
+    K: Ord + 'a,
+    V: 'a,
+{
+    /// Moves list `index` on to its next entry and queues it.
+    fn advance(&mut self, index: usize) {
+        self.values[index] = self.iters[index].next().map(|(key, value)| {
+            self.heap.push(Reverse((key, index)));
+            value
+        });
+    }
+}
+
+impl<'a, K, V> Iterator for MergeIter<'a, K, V>
+where
+    K: Ord + 'a,
+    V: 'a,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((key, index)) = self.heap.pop()?;
+        let value = self.values[index].expect("must have a value.");
+        self.advance(index);
+
+        while let Some(&Reverse((next_key, next_index))) = self.heap.peek() {
+            if next_key != key {
+                break;
+            }
+            self.heap.pop();
+            self.advance(next_index);
         }
+
+        Some((key, value))
     }
 }
 
@@ -83,6 +284,38 @@ where
     }
 }
 
+/// Each step from the back searches for the last node's predecessors, O(log n).
+impl<K, V, G> DoubleEndedIterator for IntoIter<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.remove_back().map(|data| data.into())
+    }
+}
+
+/// Consumes a list from the largest key down, see `SkipList::into_iter_rev`.
+pub struct IntoIterRev<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    inner: SkipList<K, V, G>,
+}
+
+impl<K, V, G> Iterator for IntoIterRev<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.remove_back().map(|data| data.into())
+    }
+}
+
 impl<K, V, G> IntoIterator for SkipList<K, V, G>
 where
     K: Ord,
@@ -102,24 +335,131 @@ where
     K: Ord,
     G: LevelGenerator,
 {
+    /// Consumes the list from the largest key down. Each step searches for the last node's
+    /// predecessors, O(log n). Entries left unconsumed are dropped with the iterator.
+    pub fn into_iter_rev(self) -> IntoIterRev<K, V, G> {
+        IntoIterRev { inner: self }
+    }
+
     pub fn iter(&self) -> Iter<'_, K, V> {
+        self.iter_at(self.head.next.as_ref().map(|node| node.as_ref()))
+    }
+
+    /// Like `iter`, but exact-size, so e.g. `collect` allocates once.
+    pub fn iter_pairs(&self) -> impl ExactSizeIterator<Item = (&K, &V)> {
+        ExactIter {
+            inner: self.iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Iterates from the last entry to the first. Collects the entries into a `Vec` up front,
+    /// one O(n) allocation, so it works without back links and can be walked from either end.
+    pub fn reversed(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.iter_pairs().collect::<Vec<_>>().into_iter().rev()
+    }
+
+    /// Iterates from the first key `>= key` to the end.
+    pub fn iter_from(&self, key: &K) -> Iter<'_, K, V> {
+        let start = Self::lower_bound_ptr(&self.head, Bound::Included(key));
+        self.iter_at(start.map(|ptr| unsafe { &*ptr.as_ptr() }))
+    }
+
+    fn iter_at<'a>(&'a self, current: Option<&'a Node<K, V>>) -> Iter<'a, K, V> {
         Iter {
-            current: self.head.next.as_ref().map(|node| node.as_ref()),
+            #[cfg(feature = "doubly-linked")]
+            head: &self.head,
+            #[cfg(feature = "doubly-linked")]
+            back: None,
+            current,
             _marker: std::marker::PhantomData,
         }
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        #[cfg(feature = "doubly-linked")]
+        let back = Some(Self::get_tail_nodes(&self.head).0)
+            .filter(|last_ptr| unsafe { last_ptr.as_ref() }.data.is_some());
+
         IterMut {
-            current: self.head.next.as_mut().map(|node| node.as_mut()),
+            current: self.head.next.as_deref_mut().map(NonNull::from),
+            #[cfg(feature = "doubly-linked")]
+            back,
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Walks both lists in key order, pairing up entries with equal keys.
+    pub fn merge_join<'a, W, H>(&'a self, other: &'a SkipList<K, W, H>) -> MergeJoin<'a, K, V, W>
+    where
+        H: LevelGenerator,
+    {
+        MergeJoin {
+            left: self.iter().peekable(),
+            right: other.iter().peekable(),
+        }
+    }
+
+    /// The entries whose towers reach `level`, following `forward[level]` from the head. Level
+    /// 0 holds every entry; above the head's height nothing is yielded.
+    pub fn iter_level(&self, level: usize) -> impl Iterator<Item = (&K, &V)> {
+        let first = self.head.forward.get(level).copied().flatten();
+        std::iter::successors(first, move |ptr| unsafe { ptr.as_ref() }.forward[level]).map(|ptr| {
+            unsafe { &*ptr.as_ptr() }
+                .key_value()
+                .expect("must have data.")
+        })
+    }
+
+    /// Walks the union of `lists` in key order, merging their level-0 chains lazily through a
+    /// heap, O(log k) per entry for `k` lists. A key present in several lists is yielded
+    /// once, from the earliest of them.
+    pub fn merge_iter<'a>(lists: &[&'a SkipList<K, V, G>]) -> MergeIter<'a, K, V> {
+        let mut merge = MergeIter {
+            iters: lists.iter().map(|list| list.iter()).collect(),
+            values: vec![None; lists.len()],
+            heap: BinaryHeap::with_capacity(lists.len()),
+        };
+        for index in 0..lists.len() {
+            merge.advance(index);
+        }
+        merge
+    }
+
+    /// Yields every `step`-th entry, starting with the first one.
+    ///
+    /// Panics if `step` is zero.
+    pub fn iter_step(&self, step: usize) -> impl Iterator<Item = (&K, &V)> {
+        self.iter().step_by(step)
+    }
+
+    /// Applies `f` to every value in key order, e.g. `|v| v.shrink_to_fit()` after a bulk
+    /// load. The nodes themselves are untouched.
+    pub fn shrink_values<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut V),
+    {
+        for (_, value) in self.iter_mut() {
+            f(value);
+        }
+    }
+
+    /// Visits the values in key order, threading an accumulator through `f`, and returns it.
+    pub fn scan_values_mut<A, F>(&mut self, init: A, mut f: F) -> A
+    where
+        F: FnMut(&mut A, &mut V),
+    {
+        let mut acc = init;
+        for (_, value) in self.iter_mut() {
+            f(&mut acc, value);
+        }
+        acc
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::collections::skip_list::SkipList;
+    use crate::collections::skip_list::{SkipList, MAX_LEVEL};
 
     #[test]
     fn iter() {
@@ -199,4 +539,284 @@ mod tests {
 
         assert_eq!(count, TEST_CASE);
     }
+
+    #[test]
+    fn into_iter_double_ended() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i, i.to_string());
+        }
+
+        let mut iter = sl.into_iter();
+        assert_eq!(iter.next_back(), Some((99, "99".to_string())));
+        assert_eq!(iter.next(), Some((0, "0".to_string())));
+        assert_eq!(iter.inner.len(), 98);
+        iter.inner.assert_valid();
+
+        assert!(iter
+            .by_ref()
+            .rev()
+            .take(49)
+            .map(|(k, _)| k)
+            .eq((50..99).rev()));
+        assert!(iter.by_ref().map(|(k, _)| k).eq(1..50));
+        assert_eq!(iter.next_back(), None);
+        assert!(iter.inner.is_empty());
+    }
+
+    #[test]
+    fn into_iter_rev() {
+        let mut sl = SkipList::new();
+        for i in 0..1000 {
+            sl.insert(i, i.to_string());
+        }
+
+        let mut iter = sl.into_iter_rev();
+        assert_eq!(iter.next(), Some((999, "999".to_string())));
+        assert!(iter.by_ref().take(500).map(|(k, _)| k).eq((499..999).rev()));
+        drop(iter);
+
+        assert_eq!(SkipList::<i32, i32>::new().into_iter_rev().next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "doubly-linked")]
+    fn double_ended() {
+        let mut sl = SkipList::new();
+        assert_eq!(sl.iter().next_back(), None);
+
+        for i in 0..100 {
+            sl.insert(i, i);
+        }
+        for i in (0..100).step_by(3) {
+            sl.remove(&i);
+        }
+
+        assert!(sl
+            .iter()
+            .rev()
+            .map(|(k, _)| *k)
+            .eq((0..100).rev().filter(|i| i % 3 != 0)));
+
+        let mut it = sl.iter();
+        assert_eq!(it.next(), Some((&1, &1)));
+        assert_eq!(it.next_back(), Some((&98, &98)));
+        assert_eq!(it.count(), 64);
+
+        let mut it = sl.iter();
+        let mut seen = 0;
+        while it.next().is_some() {
+            seen += 1;
+            if it.next_back().is_some() {
+                seen += 1;
+            }
+        }
+        assert_eq!(seen, sl.len());
+    }
+
+    #[test]
+    #[cfg(feature = "doubly-linked")]
+    fn iter_mut_double_ended() {
+        let mut sl = SkipList::new();
+        assert_eq!(sl.iter_mut().next_back(), None);
+
+        for i in 0..101 {
+            sl.insert(i, i);
+        }
+
+        for (i, (_, v)) in sl.iter_mut().rev().enumerate() {
+            *v += i * 1000;
+        }
+        assert!(sl.iter().all(|(k, v)| *v == k + (100 - k) * 1000));
+
+        let mut it = sl.iter_mut();
+        let mut seen = Vec::new();
+        while let Some((k, v)) = it.next() {
+            *v = 0;
+            seen.push(*k);
+            if let Some((k, v)) = it.next_back() {
+                *v = 0;
+                seen.push(*k);
+            }
+        }
+        assert_eq!(it.next_back(), None);
+        seen.sort();
+        assert!(seen.into_iter().eq(0..101));
+        assert!(sl.iter().all(|(_, v)| *v == 0));
+    }
+
+    #[test]
+    fn iter_pairs() {
+        let sl = SkipList::from_sorted((0..10).map(|i| (i, i * 2)));
+
+        let mut it = sl.iter_pairs();
+        assert_eq!(it.len(), 10);
+        assert_eq!(it.next(), Some((&0, &0)));
+        assert_eq!(it.len(), 9);
+
+        let pairs: Vec<_> = it.collect();
+        assert_eq!(pairs.len(), 9);
+        assert_eq!(pairs.last(), Some(&(&9, &18)));
+    }
+
+    #[test]
+    fn reversed() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i, i * 10);
+        }
+
+        let keys: Vec<_> = sl.reversed().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..100).rev().collect::<Vec<_>>());
+
+        let mut iter = sl.reversed();
+        assert_eq!(iter.len(), 100);
+        assert_eq!(iter.next(), Some((&99, &990)));
+        assert_eq!(iter.next_back(), Some((&0, &0)));
+        assert_eq!(iter.len(), 98);
+
+        assert_eq!(SkipList::<i32, i32>::new().reversed().next(), None);
+    }
+
+    #[test]
+    fn iter_level() {
+        let mut sl = SkipList::new();
+        for i in 0..1000 {
+            sl.insert(i, i);
+        }
+
+        assert!(sl.iter_level(0).eq(sl.iter()));
+        let histogram = sl.level_histogram();
+        for level in 0..histogram.len() {
+            let keys: Vec<_> = sl.iter_level(level).map(|(k, _)| *k).collect();
+            assert_eq!(keys.len(), histogram[level..].iter().sum::<usize>());
+            assert!(keys.windows(2).all(|w| w[0] < w[1]));
+            assert!(keys.iter().all(|k| sl.height_of(k).unwrap() >= level));
+        }
+        assert_eq!(sl.iter_level(MAX_LEVEL).count(), 0);
+    }
+
+    #[test]
+    fn shrink_values() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            let mut value = Vec::with_capacity(100);
+            value.push(i);
+            sl.insert(i, value);
+        }
+
+        sl.shrink_values(|v| v.shrink_to_fit());
+        for (i, (_, v)) in sl.iter().enumerate() {
+            assert_eq!(v, &vec![i]);
+            assert_eq!(v.capacity(), 1);
+        }
+    }
+
+    #[test]
+    fn scan_values_mut() {
+        let mut sl = SkipList::new();
+        for (k, delta) in [(3, 5), (1, 1), (2, 2), (4, -3)] {
+            sl.insert(k, delta);
+        }
+
+        let total = sl.scan_values_mut(0, |acc, value| {
+            *acc += *value;
+            *value = *acc;
+        });
+
+        assert_eq!(total, 5);
+        assert_eq!(
+            sl.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![1, 3, 8, 5]
+        );
+    }
+
+    #[test]
+    fn iter_from() {
+        let mut sl = SkipList::new();
+        for i in 0..50 {
+            sl.insert(i * 2, i);
+        }
+
+        assert!(sl.iter_from(&10).map(|(k, _)| *k).eq((10..100).step_by(2)));
+        assert!(sl.iter_from(&11).map(|(k, _)| *k).eq((12..100).step_by(2)));
+        assert_eq!(sl.iter_from(&-5).count(), 50);
+        assert_eq!(sl.iter_from(&98).count(), 1);
+        assert_eq!(sl.iter_from(&99).count(), 0);
+    }
+
+    #[test]
+    fn merge_join() {
+        let mut left = SkipList::new();
+        let mut right = SkipList::new();
+
+        for i in [1, 2, 4] {
+            left.insert(i, i * 10);
+        }
+        for i in [2, 3, 4, 5] {
+            right.insert(i, i.to_string());
+        }
+
+        let joined: Vec<_> = left
+            .merge_join(&right)
+            .map(|(k, v, w)| (*k, v.copied(), w.cloned()))
+            .collect();
+        assert_eq!(
+            joined,
+            vec![
+                (1, Some(10), None),
+                (2, Some(20), Some("2".to_string())),
+                (3, None, Some("3".to_string())),
+                (4, Some(40), Some("4".to_string())),
+                (5, None, Some("5".to_string())),
+            ]
+        );
+
+        let empty: SkipList<i32, ()> = SkipList::new();
+        assert_eq!(left.merge_join(&empty).count(), 3);
+    }
+
+    #[test]
+    fn merge_iter() {
+        let a = SkipList::from_sorted((0..10).map(|i| (i * 3, 'a')));
+        let b = SkipList::from_sorted((0..10).map(|i| (i * 2, 'b')));
+        let c = SkipList::new();
+
+        let merged: Vec<_> = SkipList::merge_iter(&[&a, &b, &c])
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        let mut keys: Vec<_> = (0..10)
+            .map(|i| i * 3)
+            .chain((0..10).map(|i| i * 2))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(merged.iter().map(|(k, _)| *k).collect::<Vec<_>>(), keys);
+        assert!(merged.contains(&(6, 'a')) && !merged.contains(&(6, 'b')));
+        assert!(merged.contains(&(4, 'b')));
+
+        let merged: Vec<_> = SkipList::merge_iter(&[&b, &a])
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        assert!(merged.contains(&(6, 'b')));
+        assert_eq!(SkipList::<i32, char>::merge_iter(&[]).count(), 0);
+    }
+
+    #[test]
+    fn iter_step() {
+        let mut sl = SkipList::new();
+
+        for i in 0..10 {
+            sl.insert(i, i * 10);
+        }
+
+        let keys: Vec<_> = sl.iter_step(3).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![0, 3, 6, 9]);
+
+        let keys: Vec<_> = sl.iter_step(1).map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..10).collect::<Vec<_>>());
+
+        let keys: Vec<_> = sl.iter_step(100).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![0]);
+    }
 }
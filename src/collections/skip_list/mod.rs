@@ -1,25 +1,51 @@
-use std::{fmt::Display, ptr::NonNull};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::Bound,
+    ptr::NonNull,
+};
 
 use data::Data;
+use finger::Finger;
+use gen_level::{expected_level, random_level_with};
 use node::Node;
+use rand::{rngs::StdRng, SeedableRng};
 
+#[cfg(feature = "rkyv")]
+mod archive;
+mod cursor;
 mod data;
+mod entry;
+mod finger;
 mod gen_level;
 mod iter;
 mod node;
+mod range;
+#[cfg(feature = "bincode")]
+mod stream;
 
+#[cfg(feature = "rkyv")]
+pub use archive::*;
+pub use cursor::*;
+pub use entry::*;
 pub use gen_level::*;
 pub use iter::*;
+pub use range::*;
 
 pub const MAX_LEVEL: usize = 32;
 
+type AdjustNodes<K, V> = [Option<NonNull<Node<K, V>>>; MAX_LEVEL];
+type Chain<K, V> = Option<Box<Node<K, V>>>;
+
 pub struct SkipList<K, V, G = DefaultGenerator>
 where
-    K: Ord,
     G: LevelGenerator,
 {
     head: Node<K, V>,
     len: usize,
+    max_level: usize,
+    capacity_limit: usize,
     gen: G,
 }
 
@@ -30,6 +56,35 @@ where
     pub fn new() -> SkipList<K, V, DefaultGenerator> {
         Default::default()
     }
+
+    /// A list sized for about `expected` entries. Only the head's forward pointers are
+    /// preallocated, `expected_level(expected)` of them, instead of the default `MAX_LEVEL`;
+    /// no nodes are allocated up front and the head still grows if a taller tower arrives.
+    pub fn with_capacity(expected: usize) -> SkipList<K, V, DefaultGenerator> {
+        let mut sl = Self::new();
+        sl.head.forward = vec![None; expected_level(expected)];
+        sl
+    }
+
+    /// A list whose `checked_insert` refuses new keys once it holds `limit` entries.
+    pub fn with_capacity_limit(limit: usize) -> SkipList<K, V, DefaultGenerator> {
+        let mut sl = Self::new();
+        sl.capacity_limit = limit;
+        sl
+    }
+
+    /// Builds a list from entries already sorted by strictly increasing key in O(n).
+    pub fn from_sorted<I>(iter: I) -> SkipList<K, V, DefaultGenerator>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut sl = Self::new();
+        let mut tails = [Some(NonNull::from(&sl.head)); MAX_LEVEL];
+        for (key, value) in iter {
+            sl.push_back(&mut tails, key, value);
+        }
+        sl
+    }
 }
 
 impl<K, V, G> SkipList<K, V, G>
@@ -41,24 +96,39 @@ where
         Self {
             head: Node::new(None, MAX_LEVEL),
             len: 0,
+            max_level: MAX_LEVEL,
+            capacity_limit: usize::MAX,
             gen,
         }
     }
 
-    fn get_adjust_nodes(
+    fn get_adjust_nodes<Q>(head: &Node<K, V>, key: &Q) -> (NonNull<Node<K, V>>, AdjustNodes<K, V>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Self::get_bound_adjust_nodes(head, Bound::Included(key))
+    }
+
+    fn get_bound_adjust_nodes<Q>(
         head: &Node<K, V>,
-        key: &K,
-    ) -> (
-        NonNull<Node<K, V>>,
-        [Option<NonNull<Node<K, V>>>; MAX_LEVEL],
-    ) {
-        let mut adjust_nodes: [Option<NonNull<Node<K, V>>>; MAX_LEVEL] = [None; MAX_LEVEL];
+        bound: Bound<&Q>,
+    ) -> (NonNull<Node<K, V>>, AdjustNodes<K, V>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut adjust_nodes: AdjustNodes<K, V> = [None; MAX_LEVEL];
 
         let mut cur_ptr = NonNull::from(head);
-        for find_level in (0..MAX_LEVEL).rev() {
+        for find_level in (0..head.level()).rev() {
             unsafe {
                 while let Some(next_ptr) = cur_ptr.as_ref().forward[find_level] {
-                    if next_ptr.as_ref().key().is_some_and(|k| k < key) {
+                    if next_ptr
+                        .as_ref()
+                        .key()
+                        .is_some_and(|k| before_lower_bound(k.borrow(), bound))
+                    {
                         cur_ptr = next_ptr;
                     } else {
                         break;
@@ -72,16 +142,136 @@ where
         (cur_ptr, adjust_nodes)
     }
 
+    fn random_level(&mut self) -> usize {
+        self.gen.random_level().min(self.max_level)
+    }
+
+    /// Caps the level of every tower at `cap`, lowering existing taller towers in O(n).
+    ///
+    /// Panics unless `1 <= cap <= MAX_LEVEL`.
+    pub fn set_max_level(&mut self, cap: usize) {
+        assert!(
+            (1..=MAX_LEVEL).contains(&cap),
+            "max level must be in 1..={}, got {}.",
+            MAX_LEVEL,
+            cap
+        );
+
+        self.max_level = cap;
+
+        let mut cur = self.head.next.as_deref_mut();
+        while let Some(node) = cur {
+            node.forward.truncate(cap);
+            cur = node.next.as_deref_mut();
+        }
+        self.head.forward.truncate(cap);
+    }
+
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        let (mut cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+        let (cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+        self.insert_after(cur_ptr, adjust_nodes, key, value)
+    }
+
+    /// Like `insert`, but first checks that `key` compares consistently with the entries it
+    /// would sit between, e.g. a float wrapper holding `NaN`, and refuses it if not.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, KeyError> {
+        let (cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+        if !Self::fits_after(unsafe { cur_ptr.as_ref() }, &key) {
+            return Err(KeyError::Incomparable);
+        }
+
+        Ok(self.insert_after(cur_ptr, adjust_nodes, key, value))
+    }
+
+    /// Like `insert`, but also returns the entry's index afterwards. One descent finds the
+    /// predecessor; nodes don't record spans, so the index is then counted by walking level 0
+    /// up to it, O(index), without comparing keys again.
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        let (cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+
+        let mut index = 0;
+        let mut node = &self.head;
+        while !std::ptr::eq(node, cur_ptr.as_ptr()) {
+            node = node.next.as_deref().expect("must reach the predecessor.");
+            index += 1;
+        }
 
+        (index, self.insert_after(cur_ptr, adjust_nodes, key, value))
+    }
+
+    /// Replaces the value if `cur_ptr`'s successor holds `key`, or else inserts a new node
+    /// after it. `cur_ptr` and `adjust_nodes` come from `get_adjust_nodes` for `key`.
+    fn insert_after(
+        &mut self,
+        mut cur_ptr: NonNull<Node<K, V>>,
+        adjust_nodes: AdjustNodes<K, V>,
+        key: K,
+        value: V,
+    ) -> Option<V> {
         if let Some(next_node) = unsafe { cur_ptr.as_mut().next.as_mut() } {
             if next_node.as_ref().key().is_some_and(|k| key == *k) {
                 return next_node.as_mut().exchange_value(value);
             }
         }
 
-        let new_level = self.gen.random_level();
+        debug_assert!(
+            Self::fits_after(unsafe { cur_ptr.as_ref() }, &key),
+            "insert: the key does not compare consistently with its neighbors."
+        );
+        self.insert_at(adjust_nodes, key, value);
+
+        None
+    }
+
+    /// Whether `key` orders after `prev` and no later than its successor, both ways round.
+    fn fits_after(prev: &Node<K, V>, key: &K) -> bool {
+        let consistent = |other: &K| key.cmp(other) == other.cmp(key).reverse();
+        key.cmp(key).is_eq()
+            && prev.key().is_none_or(|k| consistent(k) && key > k)
+            && prev
+                .next
+                .as_deref()
+                .and_then(Node::key)
+                .is_none_or(|k| consistent(k) && key <= k)
+    }
+
+    /// Like `insert`, but hands the entry back when `key` is new and the list is at its
+    /// capacity limit. Updating an existing key always succeeds. `insert` ignores the limit.
+    pub fn checked_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        let (mut cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, &key);
+
+        if let Some(next_node) = unsafe { cur_ptr.as_mut().next.as_mut() } {
+            if next_node.as_ref().key().is_some_and(|k| key == *k) {
+                return Ok(next_node.as_mut().exchange_value(value));
+            }
+        }
+
+        if self.len >= self.capacity_limit {
+            return Err((key, value));
+        }
+
+        self.insert_at(adjust_nodes, key, value);
+
+        Ok(None)
+    }
+
+    /// Splices a new node after `adjust_nodes`, the nodes a descent for `key` stopped at.
+    fn insert_at(
+        &mut self,
+        mut adjust_nodes: AdjustNodes<K, V>,
+        key: K,
+        value: V,
+    ) -> NonNull<Node<K, V>> {
+        let new_level = self.random_level();
+        for adjust_node in adjust_nodes
+            .iter_mut()
+            .take(new_level)
+            .skip(self.head.level())
+        {
+            *adjust_node = Some(NonNull::from(&self.head));
+        }
+        self.grow_head(new_level);
+
         let new_node = Node::with_key_value(key, value, new_level);
         let mut new_node = Box::new(new_node);
         let new_node_ptr = NonNull::from(new_node.as_ref());
@@ -94,66 +284,308 @@ where
             }
         }
 
+        let mut prev_ptr = adjust_nodes[0].expect("must have a previous node.");
         unsafe {
-            cur_ptr.as_mut().connect_next_node(new_node);
+            prev_ptr.as_mut().connect_next_node(new_node);
         }
 
         self.len += 1;
 
-        None
+        new_node_ptr
+    }
+
+    /// Appends after the nodes in `tails`, the last node of each level, without searching.
+    fn push_back(&mut self, tails: &mut AdjustNodes<K, V>, key: K, value: V) {
+        let last_ptr = tails[0].expect("must have a tail node.");
+        debug_assert!(
+            unsafe { last_ptr.as_ref().key() }.is_none_or(|k| *k < key),
+            "keys must be pushed in strictly increasing order."
+        );
+
+        let new_level = self.random_level();
+        self.push_back_node(tails, Box::new(Node::with_key_value(key, value, new_level)));
     }
 
     pub fn remove(&mut self, key: &K) -> Option<V> {
-        let (mut cur_ptr, adjust_nodes) = Self::get_adjust_nodes(&self.head, key);
+        self.remove_data_if(key, |_, _| true).map(|d| d.value)
+    }
+
+    /// Removes the entry for `key` only if `pred` accepts it, with a single search.
+    pub fn remove_if<F>(&mut self, key: &K, pred: F) -> Option<(K, V)>
+    where
+        F: FnOnce(&K, &V) -> bool,
+    {
+        self.remove_data_if(key, pred).map(|d| d.into())
+    }
+
+    fn remove_data_if<F>(&mut self, key: &K, pred: F) -> Option<Data<K, V>>
+    where
+        F: FnOnce(&K, &V) -> bool,
+    {
+        let (_, adjust_nodes) = Self::get_adjust_nodes(&self.head, key);
+        self.remove_after_if(&adjust_nodes, key, pred)
+    }
+
+    /// Removes the level-0 successor of `adjust_nodes` if it holds `key` and `pred` accepts it.
+    fn remove_after_if<F>(
+        &mut self,
+        adjust_nodes: &AdjustNodes<K, V>,
+        key: &K,
+        pred: F,
+    ) -> Option<Data<K, V>>
+    where
+        F: FnOnce(&K, &V) -> bool,
+    {
+        let next_node = unsafe { adjust_nodes[0]?.as_ref().next.as_ref() }?;
+        match next_node.key_value() {
+            Some((k, v)) if key == k && pred(k, v) => Some(self.remove_after(adjust_nodes)),
+            _ => None,
+        }
+    }
+
+    /// Removes the entries for `keys`, sorted in increasing order, each search resuming
+    /// where the previous one stopped. Returns how many were removed. Out-of-order keys are
+    /// still removed, but restart from the head.
+    pub fn remove_sorted_batch(&mut self, keys: &[&K]) -> usize {
+        let mut finger = Finger::new(&self.head);
+        let mut removed = 0;
+        for &key in keys {
+            let prev_ptr = finger.seek(key);
+            if unsafe { prev_ptr.as_ref() }
+                .next
+                .as_ref()
+                .is_some_and(|node| node.key() == Some(key))
+            {
+                self.remove_after(&finger.adjust_nodes);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// For each pair, inserts it if the key is new, or else merges it in with
+    /// `combine(existing, incoming)`. Searches resume where the previous one stopped, so
+    /// input sorted by key only walks forward; out-of-order keys still work, but restart from
+    /// the head.
+    pub fn upsert_many<I, F>(&mut self, iter: I, mut combine: F)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        F: FnMut(&mut V, V),
+    {
+        let mut finger = Finger::new(&self.head);
+        for (key, value) in iter {
+            let mut prev_ptr = finger.seek(&key);
+            if let Some(node) = unsafe { prev_ptr.as_mut() }.next.as_mut() {
+                if node.key() == Some(&key) {
+                    combine(node.value_mut().expect("must have data."), value);
+                    continue;
+                }
+            }
 
-        let mut del_node_ptr = None;
-        if let Some(next_node) = unsafe { cur_ptr.as_ref().next.as_ref() } {
-            if next_node.as_ref().key().is_some_and(|k| key == k) {
-                del_node_ptr = Some(NonNull::from(next_node.as_ref()));
+            let node_ptr = self.insert_at(finger.adjust_nodes, key, value);
+            let level = unsafe { node_ptr.as_ref().level() };
+            for adjust_node in finger.adjust_nodes.iter_mut().take(level) {
+                *adjust_node = Some(node_ptr);
             }
         }
+    }
 
-        let del_node_ptr = match del_node_ptr {
-            Some(ptr) => ptr,
-            None => return None,
-        };
+    /// Inserts `V::default()` for each key not already present; existing entries are left
+    /// alone. Searches resume like in `upsert_many`, so sorted input only walks forward.
+    pub fn ensure_keys<I>(&mut self, keys: I)
+    where
+        I: IntoIterator<Item = K>,
+        V: Default,
+    {
+        let mut finger = Finger::new(&self.head);
+        for key in keys {
+            let prev_ptr = finger.seek(&key);
+            let next = unsafe { prev_ptr.as_ref() }.next.as_deref();
+            if next.and_then(Node::key) == Some(&key) {
+                continue;
+            }
+
+            let node_ptr = self.insert_at(finger.adjust_nodes, key, V::default());
+            let level = unsafe { node_ptr.as_ref().level() };
+            for adjust_node in finger.adjust_nodes.iter_mut().take(level) {
+                *adjust_node = Some(node_ptr);
+            }
+        }
+    }
 
-        for (i, &node_ptr) in adjust_nodes.iter().enumerate() {
-            let mut node_ptr = node_ptr.unwrap();
+    /// Unlinks the level-0 successor of `adjust_nodes` from every level and returns its data.
+    fn remove_after(&mut self, adjust_nodes: &AdjustNodes<K, V>) -> Data<K, V> {
+        let mut cur_ptr = adjust_nodes[0].expect("must have a previous node.");
+        let del_node_ptr =
+            unsafe { cur_ptr.as_ref().next_node_ptr() }.expect("must have a next node.");
+
+        for (i, mut node_ptr) in adjust_nodes.iter().map_while(|&ptr| ptr).enumerate() {
             unsafe {
                 if node_ptr.as_ref().forward[i].is_some_and(|p| p != del_node_ptr) {
                     break;
                 }
 
                 node_ptr.as_mut().forward[i] = match del_node_ptr.as_ref().forward.get(i) {
-                    Some(ptr) => ptr.clone(),
+                    Some(ptr) => *ptr,
                     None => None,
                 };
             }
         }
 
+        #[cfg(debug_assertions)]
+        self.debug_assert_unlinked(del_node_ptr, "remove");
+
         let data = unsafe {
             let mut del_node = match cur_ptr.as_mut().next.take() {
                 Some(node) => node,
                 None => unreachable!("Must have a next node."),
             };
-            cur_ptr.as_mut().next = del_node.as_mut().next.take();
+            cur_ptr.as_mut().set_next(del_node.as_mut().next.take());
 
             del_node.data.take()
         };
 
         self.len -= 1;
-        data.map(|d| d.value)
+        data.expect("must have data.")
+    }
+
+    /// Panics if any level still links to `del_ptr`, a node being removed. Each level is
+    /// walked from the head up to the removed key; a node past it pointing back would already
+    /// break key order, which `assert_valid` checks.
+    #[cfg(debug_assertions)]
+    fn debug_assert_unlinked(&self, del_ptr: NonNull<Node<K, V>>, caller: &str) {
+        let del_key = unsafe { del_ptr.as_ref() }.key();
+        for level in 0..self.head.level() {
+            let mut next = self.head.forward[level];
+            while let Some(node_ptr) = next {
+                assert!(
+                    node_ptr != del_ptr,
+                    "{caller}: a forward pointer still points at the removed node."
+                );
+
+                let node = unsafe { node_ptr.as_ref() };
+                if node.key() >= del_key {
+                    break;
+                }
+                next = node.forward.get(level).copied().flatten();
+            }
+        }
+    }
+
+    /// The predecessors of the last node on every level, found in one descent without
+    /// comparing keys: the last node is the only one with no level-0 successor.
+    fn get_last_adjust_nodes(head: &Node<K, V>) -> AdjustNodes<K, V> {
+        let mut adjust_nodes: AdjustNodes<K, V> = [None; MAX_LEVEL];
+
+        let mut cur_ptr = NonNull::from(head);
+        for find_level in (0..head.level()).rev() {
+            unsafe {
+                while let Some(next_ptr) = cur_ptr.as_ref().forward[find_level] {
+                    if next_ptr.as_ref().next.is_none() {
+                        break;
+                    }
+                    cur_ptr = next_ptr;
+                }
+            }
+
+            adjust_nodes[find_level] = Some(cur_ptr);
+        }
+
+        adjust_nodes
+    }
+
+    /// The last node of each level among the first `index` entries, the head if there is none.
+    fn get_index_adjust_nodes(
+        head: &Node<K, V>,
+        index: usize,
+    ) -> (NonNull<Node<K, V>>, AdjustNodes<K, V>) {
+        let mut adjust_nodes: AdjustNodes<K, V> = [None; MAX_LEVEL];
+        for adjust_node in adjust_nodes.iter_mut().take(head.level()) {
+            *adjust_node = Some(NonNull::from(head));
+        }
+
+        let mut cur_ptr = NonNull::from(head);
+        for _ in 0..index {
+            cur_ptr = match unsafe { cur_ptr.as_ref().next_node_ptr() } {
+                Some(next_ptr) => next_ptr,
+                None => break,
+            };
+
+            let level = unsafe { cur_ptr.as_ref().level() };
+            for adjust_node in adjust_nodes.iter_mut().take(level) {
+                *adjust_node = Some(cur_ptr);
+            }
+        }
+
+        (cur_ptr, adjust_nodes)
+    }
+
+    /// Unlinks the nodes after `adjust_nodes` up to, but not including, `end`, or to the
+    /// end of the list if `end` is `None`. Returns the detached level-0 chain and its length.
+    fn unlink_segment(
+        &mut self,
+        adjust_nodes: &AdjustNodes<K, V>,
+        end: Option<NonNull<Node<K, V>>>,
+    ) -> (Chain<K, V>, usize) {
+        let in_segment = |node_ptr: NonNull<Node<K, V>>| match end {
+            Some(end_ptr) => unsafe { node_ptr.as_ref().key() < end_ptr.as_ref().key() },
+            None => true,
+        };
+
+        for (level, mut node_ptr) in adjust_nodes.iter().map_while(|&ptr| ptr).enumerate() {
+            unsafe {
+                let mut next = node_ptr.as_ref().forward[level];
+                while let Some(next_ptr) = next.filter(|&ptr| in_segment(ptr)) {
+                    next = next_ptr.as_ref().forward[level];
+                }
+                node_ptr.as_mut().forward[level] = next;
+            }
+        }
+
+        let mut prev_ptr = match adjust_nodes[0] {
+            Some(ptr) => ptr,
+            None => return (None, 0),
+        };
+        let mut chain = unsafe { prev_ptr.as_mut().next.take() };
+
+        let mut count = 0;
+        let mut last_ptr = None;
+        let mut cur = chain.as_ref().map(|node| NonNull::from(node.as_ref()));
+        while let Some(cur_ptr) = cur.filter(|&ptr| in_segment(ptr)) {
+            count += 1;
+            last_ptr = Some(cur_ptr);
+            cur = unsafe { cur_ptr.as_ref().next_node_ptr() };
+        }
+
+        unsafe {
+            match last_ptr {
+                Some(mut last_ptr) => prev_ptr.as_mut().set_next(last_ptr.as_mut().next.take()),
+                None => prev_ptr.as_mut().set_next(chain.take()),
+            }
+        }
+
+        self.len -= count;
+        (chain, count)
+    }
+
+    /// First node inside `bound` when it is used as a lower bound.
+    fn lower_bound_ptr(head: &Node<K, V>, bound: Bound<&K>) -> Option<NonNull<Node<K, V>>> {
+        let (cur_ptr, _) = Self::get_bound_adjust_nodes(head, bound);
+        unsafe { cur_ptr.as_ref().next_node_ptr() }
     }
 
-    fn get_node_ptr(head: &Node<K, V>, key: &K) -> Option<NonNull<Node<K, V>>> {
+    fn get_node_ptr<Q>(head: &Node<K, V>, key: &Q) -> Option<NonNull<Node<K, V>>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         let mut cur_ptr = NonNull::from(head);
 
-        for find_level in (0..MAX_LEVEL).rev() {
+        for find_level in (0..head.level()).rev() {
             unsafe {
                 while let Some(next_ptr) = cur_ptr.as_ref().forward[find_level] {
                     match next_ptr.as_ref().key() {
-                        Some(k) => match k.cmp(key) {
+                        Some(k) => match k.borrow().cmp(key) {
                             std::cmp::Ordering::Less => cur_ptr = next_ptr,
                             std::cmp::Ordering::Equal => return Some(next_ptr),
                             std::cmp::Ordering::Greater => break,
@@ -167,8 +599,13 @@ where
         None
     }
 
+    /// Looks `key` up by any borrowed form of `K`, e.g. `&str` for `String` keys.
     #[inline]
-    pub fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         match Self::get_node_ptr(&self.head, key) {
             Some(node_ptr) => unsafe { node_ptr.as_ref().value() },
             None => None,
@@ -176,166 +613,2394 @@ where
     }
 
     #[inline]
-    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         match Self::get_node_ptr(&self.head, key) {
             Some(mut node_ptr) => unsafe { node_ptr.as_mut().value_mut() },
             None => None,
         }
     }
 
-    #[inline]
-    pub fn len(&self) -> usize {
-        self.len
-    }
-
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
-    }
+    /// Mutable references to the values of `a` and `b`, `None` if `a == b` or either is absent.
+    pub fn get2_mut(&mut self, a: &K, b: &K) -> Option<(&mut V, &mut V)> {
+        let mut a_ptr = Self::get_node_ptr(&self.head, a)?;
+        let mut b_ptr = Self::get_node_ptr(&self.head, b)?;
+        if a_ptr == b_ptr {
+            return None;
+        }
 
-    #[inline]
-    pub fn clear(&mut self) {
-        while !self.is_empty() {
-            self.remove_front();
+        // Distinct keys live in distinct boxed nodes, so the two references never alias, and
+        // `&mut self` keeps the list from being touched while they are alive.
+        unsafe {
+            let a_value = a_ptr.as_mut().value_mut().expect("must have data.");
+            let b_value = b_ptr.as_mut().value_mut().expect("must have data.");
+            Some((a_value, b_value))
         }
     }
 
-    fn remove_front(&mut self) -> Option<Data<K, V>> {
-        let mut del_node = match self.head.next.take() {
-            Some(node) => node,
-            None => return None,
+    /// Swaps the values of `a` and `b` if both are present. Keys stay where they are.
+    pub fn swap_values(&mut self, a: &K, b: &K) -> bool {
+        let (mut a_ptr, mut b_ptr) = match (
+            Self::get_node_ptr(&self.head, a),
+            Self::get_node_ptr(&self.head, b),
+        ) {
+            (Some(a_ptr), Some(b_ptr)) => (a_ptr, b_ptr),
+            _ => return false,
         };
 
-        self.head.next = del_node.as_mut().next.take();
-        self.len -= 1;
-
-        for level in 0..del_node.level() {
-            self.head.forward[level] = del_node.as_ref().forward[level];
+        if a_ptr != b_ptr {
+            unsafe {
+                let a_value = a_ptr.as_mut().value_mut().expect("must have data.");
+                let b_value = b_ptr.as_mut().value_mut().expect("must have data.");
+                std::mem::swap(a_value, b_value);
+            }
         }
 
-        del_node.data.take()
+        true
     }
-}
 
-impl<K, V, G> Default for SkipList<K, V, G>
-where
-    K: Ord,
-    G: LevelGenerator + Default,
-{
-    #[inline]
-    fn default() -> SkipList<K, V, G> {
-        Self::with_gen(Default::default())
+    /// Replaces the value for `key` with `new` only if it currently equals `expected`. On a
+    /// mismatch returns a clone of the current value, `None` if `key` is absent. `&mut self`
+    /// makes the check and the swap one step; there is no cross-thread atomicity.
+    pub fn compare_and_swap(&mut self, key: &K, expected: &V, new: V) -> Result<(), Option<V>>
+    where
+        V: PartialEq + Clone,
+    {
+        match self.get_mut(key) {
+            Some(value) if value == expected => {
+                *value = new;
+                Ok(())
+            }
+            current => Err(current.cloned()),
+        }
     }
-}
 
-impl<K, V, G> Drop for SkipList<K, V, G>
-where
-    K: Ord,
-    G: LevelGenerator,
-{
-    fn drop(&mut self) {
-        let mut node = match self.head.next.take() {
-            Some(node) => node,
-            None => return,
-        };
+    /// A clone of the value for `key`, or `V::default()`. Never inserts.
+    #[inline]
+    pub fn get_or_default(&self, key: &K) -> V
+    where
+        V: Default + Clone,
+    {
+        self.get(key).cloned().unwrap_or_default()
+    }
 
-        while let Some(next) = node.next.take() {
-            drop(node);
-            node = next;
+    /// Like slice `binary_search`: `Ok(index)` of `key`, or `Err` with the index it would be
+    /// inserted at. Nodes don't record spans, so this walks level 0 in O(index).
+    pub fn search(&self, key: &K) -> Result<usize, usize> {
+        let mut index = 0;
+        for (k, _) in self.iter() {
+            match k.cmp(key) {
+                std::cmp::Ordering::Less => index += 1,
+                std::cmp::Ordering::Equal => return Ok(index),
+                std::cmp::Ordering::Greater => break,
+            }
         }
+        Err(index)
     }
-}
 
-impl<K, V, G> Clone for SkipList<K, V, G>
-where
-    K: Ord + Clone,
-    V: Clone,
-    G: LevelGenerator + Clone,
-{
-    fn clone(&self) -> Self {
-        let mut new_sl = SkipList::with_gen(self.gen.clone());
-        for (key, value) in self.iter() {
-            new_sl.insert(key.clone(), value.clone());
-        }
-        new_sl
+    #[inline]
+    pub fn first_key(&self) -> Option<&K> {
+        self.head.next.as_ref().and_then(|node| node.key())
     }
-}
 
-impl<K, V> Display for SkipList<K, V>
-where
-    K: Ord + Display,
-    V: Display,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut it = self.iter();
+    pub fn last_key(&self) -> Option<&K> {
+        let (last_ptr, _) = Self::get_tail_nodes(&self.head);
+        unsafe { last_ptr.as_ref().key() }
+    }
 
-        write!(f, "{{")?;
-        if let Some((key, value)) = it.next() {
-            write!(f, "({}: {})", key, value)?;
-        }
-        for (key, value) in it {
-            write!(f, ",({}: {})", key, value)?;
-        }
-        write!(f, "}}")
+    /// The smallest and largest keys together, O(log n) for the largest.
+    pub fn key_range(&self) -> Option<(&K, &K)> {
+        Some((self.first_key()?, self.last_key()?))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::SkipList;
+    /// The smallest entry, in O(1).
+    #[inline]
+    pub fn peek_min(&self) -> Option<(&K, &V)> {
+        self.head.next.as_ref().and_then(|node| node.key_value())
+    }
 
-    #[test]
-    fn insert_and_get() {
-        let mut skiplist = SkipList::new();
+    /// The largest entry, found by a descent in O(log n).
+    pub fn peek_max(&self) -> Option<(&K, &V)> {
+        let (last_ptr, _) = Self::get_tail_nodes(&self.head);
+        unsafe { &*last_ptr.as_ptr() }.key_value()
+    }
 
-        for i in 0..100000 {
-            skiplist.insert(i, i);
-        }
-        for i in 0..100000 {
-            assert_eq!(skiplist.get(&i), Some(&i));
-        }
+    /// The value of the greatest key `<= key`, i.e. the last known value at `key` in a step
+    /// function. One descent, O(log n).
+    pub fn value_at_or_before(&self, key: &K) -> Option<&V> {
+        let (floor_ptr, _) = Self::get_bound_adjust_nodes(&self.head, Bound::Excluded(key));
+        unsafe { &*floor_ptr.as_ptr() }.value()
     }
 
-    #[test]
-    fn update_value() {
-        let mut skiplist = SkipList::new();
+    /// The entry at `index` in key order. Nodes don't record spans, so this walks level 0 in
+    /// O(index).
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.iter().nth(index)
+    }
 
-        skiplist.insert(1, "value1");
-        assert_eq!(skiplist.insert(1, "value2"), Some("value1"));
-        assert_eq!(skiplist.get(&1), Some(&"value2"));
+    /// Like `get_index`, but panics like `Vec` indexing if `index` is out of bounds.
+    pub fn index_entry(&self, index: usize) -> (&K, &V) {
+        match self.get_index(index) {
+            Some(entry) => entry,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            ),
+        }
     }
 
-    #[test]
-    fn remove() {
-        let mut skiplist = SkipList::new();
+    /// Like `==` on the entries, but the tower heights must match node for node too.
+    pub fn structural_eq(&self, other: &Self) -> bool
+    where
+        V: PartialEq,
+    {
+        if self.len != other.len {
+            return false;
+        }
 
-        skiplist.insert(1, "value1");
-        skiplist.insert(2, "value2");
-        skiplist.insert(3, "value3");
+        let mut left = self.head.next.as_deref();
+        let mut right = other.head.next.as_deref();
+        while let (Some(l), Some(r)) = (left, right) {
+            if l.level() != r.level() || l.key_value() != r.key_value() {
+                return false;
+            }
+            left = l.next.as_deref();
+            right = r.next.as_deref();
+        }
 
-        assert_eq!(skiplist.remove(&2), Some("value2"));
-        assert_eq!(skiplist.get(&2), None);
-        assert_eq!(skiplist.len(), 2);
+        left.is_none() && right.is_none()
     }
 
-    #[test]
-    fn remove_non_existent() {
-        let mut skiplist = SkipList::new();
+    /// Whether both lists hold the same keys, whatever their values.
+    pub fn keys_eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().map(|(k, _)| k).eq(other.iter().map(|(k, _)| k))
+    }
 
-        skiplist.insert(1, "value1");
-        assert_eq!(skiplist.remove(&2), None);
+    /// Number of entries matching `f`. Always scans the whole list, O(n).
+    pub fn count_matching<F>(&self, mut f: F) -> usize
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter().filter(|(k, v)| f(k, v)).count()
     }
 
-    #[test]
-    fn clear() {
-        let mut skiplist = SkipList::new();
+    /// The first entry in key order matching `f`, stopping as soon as it is found.
+    pub fn find<F>(&self, mut f: F) -> Option<(&K, &V)>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter().find(|(k, v)| f(k, v))
+    }
 
-        skiplist.insert(1, "value1");
-        skiplist.insert(2, "value2");
-        skiplist.clear();
+    /// The entry with the smallest value, the first in key order on ties. Scans the whole
+    /// list, O(n).
+    pub fn min_by_value(&self) -> Option<(&K, &V)>
+    where
+        V: Ord,
+    {
+        self.iter().min_by_key(|&(_, v)| v)
+    }
 
-        assert_eq!(skiplist.len(), 0);
-        assert!(skiplist.is_empty());
+    /// The entry with the largest value, the last in key order on ties. O(n).
+    pub fn max_by_value(&self) -> Option<(&K, &V)>
+    where
+        V: Ord,
+    {
+        self.iter().max_by_key(|&(_, v)| v)
+    }
+
+    /// Like `min_by_value`, comparing `f(value)` instead.
+    pub fn min_by_value_key<B, F>(&self, mut f: F) -> Option<(&K, &V)>
+    where
+        B: Ord,
+        F: FnMut(&V) -> B,
+    {
+        self.iter().min_by_key(|&(_, v)| f(v))
+    }
+
+    /// Like `max_by_value`, comparing `f(value)` instead.
+    pub fn max_by_value_key<B, F>(&self, mut f: F) -> Option<(&K, &V)>
+    where
+        B: Ord,
+        F: FnMut(&V) -> B,
+    {
+        self.iter().max_by_key(|&(_, v)| f(v))
+    }
+
+    /// How many different values the list holds. Values aren't indexed, so this collects
+    /// them into a `BTreeSet`, O(n log n).
+    pub fn count_distinct_values(&self) -> usize
+    where
+        V: Ord,
+    {
+        self.iter().map(|(_, v)| v).collect::<BTreeSet<_>>().len()
+    }
+
+    /// Clones every entry into a sorted `Vec`, a plain `Send` copy that `from_sorted` can
+    /// rebuild a list from.
+    pub fn snapshot(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.clone_to_vec()
+    }
+
+    /// Clones every entry into a `Vec` sized up front, with a direct level-0 walk instead of
+    /// going through `Iter`.
+    pub fn clone_to_vec(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut entries = Vec::with_capacity(self.len);
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            if let Some(data) = node.data.as_ref() {
+                entries.push((data.key.clone(), data.value.clone()));
+            }
+            cur = node.next.as_deref();
+        }
+        entries
+    }
+
+    /// Clones the entries into a `BTreeMap`. This is a copy, not a view. The entries arrive
+    /// sorted, so `BTreeMap` bulk-builds it in O(n).
+    pub fn collect_btree(&self) -> BTreeMap<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// Looks up keys sorted in increasing order, each search resuming where the previous
+    /// one stopped. Out-of-order keys are still found, but restart from the head.
+    pub fn get_batch<'a>(&'a self, keys: &[&K]) -> Vec<Option<&'a V>> {
+        let mut finger = Finger::new(&self.head);
+        keys.iter()
+            .map(|&key| {
+                let prev_ptr = finger.seek(key);
+                let node = unsafe { &*prev_ptr.as_ptr() }.next.as_deref()?;
+                node.key_value().filter(|(k, _)| *k == key).map(|(_, v)| v)
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Counts the nodes on level 0 one by one, independently of the cached `len`. O(n), for
+    /// tests and diagnostics.
+    pub fn count_nodes(&self) -> usize {
+        let mut count = 0;
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            if node.data.is_some() {
+                count += 1;
+            }
+            cur = node.next.as_deref();
+        }
+        count
+    }
+
+    /// Panics unless the level-0 keys strictly increase, `len` matches the node count, and
+    /// every level links exactly the towers that reach it, in order. O(n), for fuzzing and
+    /// tests.
+    pub fn assert_valid(&self) {
+        let height = self.head.level();
+        let mut last_ptrs = vec![NonNull::from(&self.head); height];
+
+        let mut count = 0;
+        let mut prev_key = None;
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            let key = node.key().expect("assert_valid: a node has no data.");
+            assert!(
+                prev_key.is_none_or(|prev| prev < key),
+                "assert_valid: keys are not strictly increasing."
+            );
+            assert!(
+                node.level() <= height,
+                "assert_valid: a tower is taller than the head."
+            );
+
+            let node_ptr = NonNull::from(node);
+            for (level, last_ptr) in last_ptrs.iter_mut().enumerate().take(node.level()) {
+                assert_eq!(
+                    unsafe { last_ptr.as_ref() }.forward[level],
+                    Some(node_ptr),
+                    "assert_valid: level {} skips a tower.",
+                    level
+                );
+                *last_ptr = node_ptr;
+            }
+
+            count += 1;
+            prev_key = Some(key);
+            cur = node.next.as_deref();
+        }
+
+        for (level, last_ptr) in last_ptrs.iter().enumerate() {
+            assert!(
+                unsafe { last_ptr.as_ref() }.forward[level].is_none(),
+                "assert_valid: level {} runs past the last tower.",
+                level
+            );
+        }
+        assert_eq!(count, self.len, "assert_valid: `len` is out of sync.");
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        while !self.is_empty() {
+            self.remove_front();
+        }
+    }
+
+    /// Appends entries whose keys strictly increase and all exceed `last_key`, linking each
+    /// after the current tails with a fresh random level, without searching. Debug builds
+    /// panic otherwise.
+    pub fn extend_sorted<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let (_, mut tails) = Self::get_tail_nodes(&self.head);
+        for tail in tails.iter_mut().skip(self.head.level()) {
+            *tail = Some(NonNull::from(&self.head));
+        }
+
+        for (key, value) in iter {
+            self.push_back(&mut tails, key, value);
+        }
+    }
+
+    /// Moves all of `other` after the end of `self` without comparing keys, in O(log n).
+    ///
+    /// Every key of `other` must be greater than every key of `self`; debug builds panic otherwise.
+    pub fn append_disjoint(&mut self, mut other: SkipList<K, V, G>) {
+        debug_assert!(
+            match (self.last_key(), other.first_key()) {
+                (Some(last), Some(first)) => last < first,
+                _ => true,
+            },
+            "append_disjoint: every key of `other` must be greater than the last key of `self`."
+        );
+
+        self.splice_back(&mut other);
+    }
+
+    /// Moves every node of `other` after the end of `self`, leaving `other` empty. Keys are
+    /// not compared. Towers taller than `self`'s level cap are cut down to it first.
+    fn splice_back(&mut self, other: &mut SkipList<K, V, G>) {
+        if other.is_empty() {
+            return;
+        }
+        other.truncate_towers(self.max_level);
+
+        let (mut last_ptr, mut tail_nodes) = Self::get_tail_nodes(&self.head);
+        for tail_node in tail_nodes
+            .iter_mut()
+            .take(other.head.level())
+            .skip(self.head.level())
+        {
+            *tail_node = Some(NonNull::from(&self.head));
+        }
+        self.grow_head(other.head.level());
+
+        for (level, mut tail_ptr) in tail_nodes
+            .iter()
+            .take(other.head.level())
+            .map_while(|&ptr| ptr)
+            .enumerate()
+        {
+            unsafe {
+                tail_ptr.as_mut().forward[level] = other.head.forward[level].take();
+            }
+        }
+
+        unsafe {
+            last_ptr.as_mut().set_next(other.head.next.take());
+        }
+
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Moves every entry into `dest`, leaving `self` empty. On equal keys `self`'s value
+    /// wins. If all of `self` sorts after `dest`, the nodes are spliced over in O(log n),
+    /// plus one step per tower cut down to `dest`'s level cap; otherwise each entry is
+    /// inserted, O(n log n). Like `insert`, this ignores `dest`'s capacity limit.
+    pub fn drain_into(&mut self, dest: &mut SkipList<K, V, G>) {
+        let disjoint = match (dest.last_key(), self.first_key()) {
+            (Some(last), Some(first)) => last < first,
+            _ => true,
+        };
+
+        if disjoint {
+            dest.splice_back(self);
+            return;
+        }
+
+        while let Some(data) = self.remove_front() {
+            dest.insert(data.key, data.value);
+        }
+    }
+
+    /// Removes, in one level-0 pass, every entry `f` rejects. `f` also sees the last kept
+    /// entry, `None` at the start. Returns how many entries were removed.
+    fn retain_nodes<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(Option<&Data<K, V>>, &mut Data<K, V>) -> bool,
+    {
+        let (mut prev_ptr, mut adjust_nodes) = Self::get_index_adjust_nodes(&self.head, 0);
+
+        let mut removed = 0;
+        while let Some(mut cur_ptr) = unsafe { prev_ptr.as_ref().next_node_ptr() } {
+            let keep = unsafe {
+                let cur_data = cur_ptr.as_mut().data.as_mut().expect("must have data.");
+                f(prev_ptr.as_ref().data.as_ref(), cur_data)
+            };
+
+            let level = unsafe { cur_ptr.as_ref().level() };
+            if keep {
+                for adjust_node in adjust_nodes.iter_mut().take(level) {
+                    *adjust_node = Some(cur_ptr);
+                }
+                prev_ptr = cur_ptr;
+                continue;
+            }
+
+            for (i, mut node_ptr) in adjust_nodes
+                .iter()
+                .take(level)
+                .map_while(|&ptr| ptr)
+                .enumerate()
+            {
+                unsafe {
+                    node_ptr.as_mut().forward[i] = cur_ptr.as_ref().forward[i];
+                }
+            }
+
+            unsafe {
+                let mut del_node = prev_ptr
+                    .as_mut()
+                    .next
+                    .take()
+                    .expect("must have a next node.");
+                prev_ptr.as_mut().set_next(del_node.next.take());
+            }
+            removed += 1;
+        }
+
+        self.len -= removed;
+        removed
+    }
+
+    /// Keeps only the entries `f` accepts, in one level-0 pass.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain_nodes(|_, cur| f(&cur.key, &mut cur.value));
+    }
+
+    /// Like `retain`, but returns how many entries were removed.
+    pub fn retain_count<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain_nodes(|_, cur| f(&cur.key, &mut cur.value))
+    }
+
+    /// Like `retain`, but `f` also gets each entry's index from before this call: the first
+    /// entry is 0 and removals during the pass don't shift later indices.
+    pub fn retain_indexed<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &K, &V) -> bool,
+    {
+        let mut index = 0;
+        self.retain_nodes(|_, cur| {
+            let keep = f(index, &cur.key, &cur.value);
+            index += 1;
+            keep
+        });
+    }
+
+    /// Like `retain`, but `f` only sees the key.
+    pub fn retain_keys<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K) -> bool,
+    {
+        self.retain(|k, _| f(k));
+    }
+
+    /// Like `retain`, but `f` only sees the value.
+    pub fn retain_values<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut V) -> bool,
+    {
+        self.retain(|_, v| f(v));
+    }
+
+    /// Keeps only the first key of each run of adjacent equal values.
+    pub fn dedup_values(&mut self)
+    where
+        V: PartialEq,
+    {
+        self.retain_nodes(|prev, cur| prev.is_none_or(|prev| prev.value != cur.value));
+    }
+
+    /// Removes each entry that `same(prev_key, prev_value, key, value)` considers a duplicate
+    /// of the last kept entry before it, like `Vec::dedup_by`. One level-0 pass.
+    pub fn dedup_by<F>(&mut self, mut same: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> bool,
+    {
+        self.retain_nodes(|prev, cur| {
+            prev.is_none_or(|prev| !same(&prev.key, &prev.value, &cur.key, &cur.value))
+        });
+    }
+
+    /// Removes and returns the entry at `index` in key order, `None` if `index >= len`.
+    /// Nodes don't record spans, so finding it walks level 0 in O(index).
+    pub fn pop_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len {
+            return None;
+        }
+
+        let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, index);
+        Some(self.remove_after(&adjust_nodes).into())
+    }
+
+    /// Removes and returns the smallest entry, in O(1).
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        self.remove_front().map(|data| data.into())
+    }
+
+    /// Like `pop_first`, but only if `pred` accepts the smallest entry; otherwise it stays.
+    pub fn pop_front_if<F>(&mut self, pred: F) -> Option<(K, V)>
+    where
+        F: FnOnce(&K, &V) -> bool,
+    {
+        let (key, value) = self.peek_min()?;
+        if pred(key, value) {
+            self.pop_first()
+        } else {
+            None
+        }
+    }
+
+    /// Removes and returns the largest entry, in O(log n). Even with `doubly-linked` this is
+    /// a descent: the last tower's predecessors on every level have to be found to unlink it.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        self.remove_back().map(|data| data.into())
+    }
+
+    /// Keeps only the first `len` entries. Finding the cut walks level 0, so O(len).
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, len);
+        let (chain, _) = self.unlink_segment(&adjust_nodes, None);
+        drop_chain(chain);
+    }
+
+    /// Removes the entries from `index` on and returns them in key order, leaving the first
+    /// `index`. Finding the cut walks level 0, so O(n) overall.
+    pub fn split_off_index_vec(&mut self, index: usize) -> Vec<(K, V)> {
+        if index >= self.len {
+            return Vec::new();
+        }
+
+        let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, index);
+        let (mut chain, count) = self.unlink_segment(&adjust_nodes, None);
+
+        let mut entries = Vec::with_capacity(count);
+        while let Some(mut node) = chain {
+            chain = node.next.take();
+            entries.extend(node.data.take().map(Data::into));
+        }
+        entries
+    }
+
+    /// Keeps only the `n` smallest entries, the same as `truncate(n)`.
+    pub fn retain_smallest(&mut self, n: usize) {
+        self.truncate(n);
+    }
+
+    /// Keeps only the `n` largest entries. Finding the cut walks level 0, so O(len - n).
+    pub fn retain_largest(&mut self, n: usize) {
+        if n >= self.len {
+            return;
+        }
+
+        let (last_ptr, _) = Self::get_index_adjust_nodes(&self.head, self.len - n);
+        let end = unsafe { last_ptr.as_ref().next_node_ptr() };
+        let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, 0);
+        let (chain, _) = self.unlink_segment(&adjust_nodes, end);
+        drop_chain(chain);
+    }
+
+    /// Splits the entries into `parts` contiguous sorted lists whose lengths differ by at most
+    /// one, the longer ones first. Some lists are empty if `parts > len`. Streams the entries
+    /// into tail-appended lists, O(n).
+    ///
+    /// Panics if `parts` is zero.
+    pub fn split_into(self, parts: usize) -> Vec<SkipList<K, V>> {
+        assert!(parts != 0, "parts must be non-zero.");
+
+        let (base, extra) = (self.len / parts, self.len % parts);
+        let mut entries = self.into_iter();
+        (0..parts)
+            .map(|part| {
+                let size = base + usize::from(part < extra);
+                SkipList::from_sorted(entries.by_ref().take(size))
+            })
+            .collect()
+    }
+
+    /// Rekeys every entry with `f`, which must be strictly monotonic, either order-preserving
+    /// or order-reversing. The direction is read off the first two keys only, so a
+    /// non-monotonic `f` is caught by a debug assertion, not in release builds. Every tower
+    /// keeps its height, mirrored when `f` reverses the order, and the list keeps its
+    /// generator and level settings. O(n), buffering the entries only when reversing.
+    pub fn map_keys_monotonic<L, F>(mut self, f: F) -> SkipList<L, V, G>
+    where
+        L: Ord,
+        F: Fn(K) -> L,
+        G: Clone,
+    {
+        let mut new_sl = SkipList {
+            head: Node::new(None, self.head.level()),
+            len: 0,
+            max_level: self.max_level,
+            capacity_limit: self.capacity_limit,
+            gen: self.gen.clone(),
+        };
+
+        let mut chain = self.head.next.take();
+        self.len = 0;
+        let mut entries = std::iter::from_fn(move || {
+            let mut node = chain.take()?;
+            chain = node.next.take();
+            let data = node.data.take().expect("must have data.");
+            Some((f(data.key), data.value, node.level()))
+        });
+        let first = entries.next();
+        let second = entries.next();
+
+        let reversing = match (&first, &second) {
+            (Some((a, _, _)), Some((b, _, _))) => a > b,
+            _ => false,
+        };
+        let entries = first.into_iter().chain(second).chain(entries);
+
+        let mut tails = [Some(NonNull::from(&new_sl.head)); MAX_LEVEL];
+        let mut push = |(key, value, level): (L, V, usize)| {
+            let last_ptr = tails[0].expect("must have a tail node.");
+            debug_assert!(
+                unsafe { last_ptr.as_ref() }
+                    .key()
+                    .is_none_or(|last| *last < key),
+                "map_keys_monotonic: mapped keys are not strictly increasing."
+            );
+            let new_node = Node::with_key_value(key, value, level);
+            new_sl.push_back_node(&mut tails, Box::new(new_node));
+        };
+
+        if reversing {
+            let entries: Vec<_> = entries.collect();
+            entries.into_iter().rev().for_each(&mut push);
+        } else {
+            entries.for_each(&mut push);
+        }
+
+        new_sl
+    }
+
+    /// Presizes the head for about `expected_len` entries. Nodes are not preallocated.
+    pub fn reserve_levels(&mut self, expected_len: usize) {
+        self.grow_head(expected_level(expected_len));
+    }
+
+    /// Reassigns every tower height from an RNG seeded with `seed`, so the same contents
+    /// always get the same shape. The nodes and their entries are kept, only the towers are
+    /// rebuilt, in O(n). Heights still respect `set_max_level`.
+    pub fn rebuild_with_seed(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut chain = self.head.next.take();
+        self.head.forward.fill(None);
+        self.len = 0;
+
+        let mut tails = [Some(NonNull::from(&self.head)); MAX_LEVEL];
+        while let Some(mut node) = chain {
+            chain = node.next.take();
+            node.forward = vec![None; random_level_with(&mut rng).min(self.max_level)];
+            self.push_back_node(&mut tails, node);
+        }
+    }
+
+    /// Replaces the contents with entries sorted by strictly increasing key, like assigning
+    /// `from_sorted`, but keeps the head and its forward pointers. Debug builds panic on
+    /// unsorted input.
+    pub fn rebuild_from_sorted<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.clear();
+
+        let mut tails = [Some(NonNull::from(&self.head)); MAX_LEVEL];
+        for (key, value) in iter {
+            self.push_back(&mut tails, key, value);
+        }
+    }
+
+    /// How many levels `key`'s tower reaches above level 0, `None` if `key` is absent. Towers
+    /// of height `h` are counted at `level_histogram()[h]`.
+    pub fn height_of(&self, key: &K) -> Option<usize> {
+        Self::get_node_ptr(&self.head, key).map(|node_ptr| unsafe { node_ptr.as_ref().level() } - 1)
+    }
+
+    /// Number of towers of each height: `histogram[h - 1]` towers span `h` levels, i.e. have
+    /// `height_of` `h - 1`. Empty for an empty list.
+    pub fn level_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            if histogram.len() < node.level() {
+                histogram.resize(node.level(), 0);
+            }
+            histogram[node.level() - 1] += 1;
+            cur = node.next.as_deref();
+        }
+        histogram
+    }
+
+    /// Breaks the list's memory down into pointer overhead and payload, in one level-0 walk.
+    pub fn memory_report(&self) -> MemoryReport {
+        let slot_size = std::mem::size_of::<Option<NonNull<Node<K, V>>>>();
+
+        let mut node_count = 0;
+        let mut total_forward_slots = 0;
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            node_count += 1;
+            total_forward_slots += node.forward.capacity();
+            cur = node.next.as_deref();
+        }
+
+        MemoryReport {
+            node_count,
+            total_forward_slots,
+            forward_bytes: total_forward_slots * slot_size,
+            payload_bytes: std::mem::size_of::<(K, V)>() * self.len,
+            head_overhead_bytes: std::mem::size_of::<Node<K, V>>()
+                + self.head.forward.capacity() * slot_size,
+        }
+    }
+
+    /// Drops the head's unused top levels and any spare capacity in every tower, in O(n).
+    /// Nodes don't move, so nothing is relinked.
+    pub fn shrink_to_fit(&mut self) {
+        let used = self
+            .head
+            .forward
+            .iter()
+            .rposition(|ptr| ptr.is_some())
+            .map_or(0, |level| level + 1);
+        self.head.forward.truncate(used);
+        self.head.forward.shrink_to_fit();
+
+        let mut cur = self.head.next.as_deref_mut();
+        while let Some(node) = cur {
+            node.forward.shrink_to_fit();
+            cur = node.next.as_deref_mut();
+        }
+    }
+
+    /// Like `clear`, but also frees the head's forward pointers. They grow back on insert.
+    pub fn clear_and_shrink(&mut self) {
+        self.clear();
+        self.head.forward = Vec::new();
+    }
+
+    fn remove_back(&mut self) -> Option<Data<K, V>> {
+        self.head.next.as_ref()?;
+
+        let adjust_nodes = Self::get_last_adjust_nodes(&self.head);
+        Some(self.remove_after(&adjust_nodes))
+    }
+
+    fn remove_front(&mut self) -> Option<Data<K, V>> {
+        let mut del_node = self.head.next.take()?;
+
+        self.head.set_next(del_node.as_mut().next.take());
+        self.len -= 1;
+
+        for level in 0..del_node.level() {
+            self.head.forward[level] = del_node.as_ref().forward[level];
+        }
+
+        #[cfg(debug_assertions)]
+        self.debug_assert_unlinked(NonNull::from(del_node.as_ref()), "remove_front");
+
+        del_node.data.take()
+    }
+}
+
+/// Helpers that never compare keys.
+impl<K, V, G> SkipList<K, V, G>
+where
+    G: LevelGenerator,
+{
+    /// The last node of each level, the head for empty levels.
+    fn get_tail_nodes(head: &Node<K, V>) -> (NonNull<Node<K, V>>, AdjustNodes<K, V>) {
+        let mut tail_nodes: AdjustNodes<K, V> = [None; MAX_LEVEL];
+
+        let mut cur_ptr = NonNull::from(head);
+        for find_level in (0..head.level()).rev() {
+            unsafe {
+                while let Some(next_ptr) = cur_ptr.as_ref().forward[find_level] {
+                    cur_ptr = next_ptr;
+                }
+            }
+
+            tail_nodes[find_level] = Some(cur_ptr);
+        }
+
+        (cur_ptr, tail_nodes)
+    }
+
+    /// Makes the head at least `level` high, linking the new levels to nothing.
+    fn grow_head(&mut self, level: usize) {
+        if self.head.level() < level {
+            self.head.forward.resize(level, None);
+        }
+    }
+
+    /// Cuts every tower down to at most `cap` levels. The taller ones are exactly those
+    /// linked on level `cap`, so only they are visited.
+    fn truncate_towers(&mut self, cap: usize) {
+        let mut next = self.head.forward.get(cap).copied().flatten();
+        while let Some(mut node_ptr) = next {
+            unsafe {
+                next = node_ptr.as_ref().forward[cap];
+                node_ptr.as_mut().forward.truncate(cap);
+            }
+        }
+        self.head.forward.truncate(cap);
+    }
+
+    /// Like `push_back`, but appends a detached node as is, keeping its level.
+    fn push_back_node(&mut self, tails: &mut AdjustNodes<K, V>, new_node: Box<Node<K, V>>) {
+        let mut last_ptr = tails[0].expect("must have a tail node.");
+        let new_level = new_node.level();
+        self.grow_head(new_level);
+
+        let new_node_ptr = NonNull::from(new_node.as_ref());
+
+        for (level, tail) in tails.iter_mut().enumerate().take(new_level) {
+            let mut tail_ptr = tail.expect("must have a tail node.");
+            unsafe {
+                tail_ptr.as_mut().forward[level] = Some(new_node_ptr);
+            }
+            *tail = Some(new_node_ptr);
+        }
+
+        unsafe {
+            last_ptr.as_mut().connect_next_node(new_node);
+        }
+
+        self.len += 1;
+    }
+}
+
+/// Drops a detached level-0 chain node by node instead of recursively.
+fn drop_chain<K, V>(mut node: Chain<K, V>) {
+    while let Some(mut cur) = node {
+        node = cur.next.take();
+    }
+}
+
+fn before_lower_bound<K: Ord + ?Sized>(key: &K, bound: Bound<&K>) -> bool {
+    match bound {
+        Bound::Included(k) => key < k,
+        Bound::Excluded(k) => key <= k,
+        Bound::Unbounded => false,
+    }
+}
+
+fn within_upper_bound<K: Ord>(key: &K, bound: Bound<&K>) -> bool {
+    match bound {
+        Bound::Included(k) => key <= k,
+        Bound::Excluded(k) => key < k,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Where a list's memory goes, see `SkipList::memory_report`. Estimates: heap data owned by
+/// keys and values, and allocator overhead, are not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Nodes on level 0, not counting the head.
+    pub node_count: usize,
+    /// Allocated forward pointer slots over all nodes, spare capacity included.
+    pub total_forward_slots: usize,
+    pub forward_bytes: usize,
+    /// `size_of::<(K, V)>()` per entry.
+    pub payload_bytes: usize,
+    /// The head node and its forward pointers.
+    pub head_overhead_bytes: usize,
+}
+
+/// Why a key was rejected, by `SkipList::try_insert` or when loading a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// The key's `Ord` disagrees with itself against its neighbors.
+    Incomparable,
+    /// The keys being loaded are not in strictly increasing order.
+    Unsorted,
+}
+
+impl Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyError::Incomparable => write!(f, "key does not compare consistently"),
+            KeyError::Unsorted => write!(f, "keys are not in strictly increasing order"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+impl<K, V, G> Default for SkipList<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator + Default,
+{
+    #[inline]
+    fn default() -> SkipList<K, V, G> {
+        Self::with_gen(Default::default())
+    }
+}
+
+impl<K, V, G> Drop for SkipList<K, V, G>
+where
+    G: LevelGenerator,
+{
+    fn drop(&mut self) {
+        drop_chain(self.head.next.take());
+    }
+}
+
+/// Copies the list node for node, tower heights included, so keys are never compared.
+impl<K, V, G> Clone for SkipList<K, V, G>
+where
+    K: Clone,
+    V: Clone,
+    G: LevelGenerator + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut new_sl = SkipList {
+            head: Node::new(None, self.head.level()),
+            len: 0,
+            max_level: self.max_level,
+            capacity_limit: self.capacity_limit,
+            gen: self.gen.clone(),
+        };
+
+        let mut tails = [Some(NonNull::from(&new_sl.head)); MAX_LEVEL];
+        let mut cur = self.head.next.as_deref();
+        while let Some(node) = cur {
+            let data = node.data.as_ref().expect("must have data.");
+            let new_node = Node::with_key_value(data.key.clone(), data.value.clone(), node.level());
+            new_sl.push_back_node(&mut tails, Box::new(new_node));
+            cur = node.next.as_deref();
+        }
+        new_sl
+    }
+}
+
+impl<K, V> Display for SkipList<K, V>
+where
+    K: Ord + Display,
+    V: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut it = self.iter();
+
+        write!(f, "{{")?;
+        if let Some((key, value)) = it.next() {
+            write!(f, "({}: {})", key, value)?;
+        }
+        for (key, value) in it {
+            write!(f, ",({}: {})", key, value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expected_level, KeyError, SkipList, MAX_LEVEL};
+
+    #[test]
+    fn insert_and_get() {
+        let mut skiplist = SkipList::new();
+
+        for i in 0..100000 {
+            skiplist.insert(i, i);
+        }
+        for i in 0..100000 {
+            assert_eq!(skiplist.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn get_or_default() {
+        let mut skiplist = SkipList::new();
+
+        skiplist.insert(1, 10);
+        assert_eq!(skiplist.get_or_default(&1), 10);
+        assert_eq!(skiplist.get_or_default(&2), 0);
+        assert_eq!(skiplist.len(), 1);
+        assert_eq!(skiplist.get(&2), None);
+    }
+
+    #[test]
+    fn min_and_max_by_value() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.min_by_value(), None);
+
+        for (k, v) in [(1, 5), (2, 1), (3, 9), (4, 1), (5, 9)] {
+            skiplist.insert(k, v);
+        }
+
+        assert_eq!(skiplist.min_by_value(), Some((&2, &1)));
+        assert_eq!(skiplist.max_by_value(), Some((&5, &9)));
+        assert_eq!(
+            skiplist.min_by_value_key(|v| (v - 5i32).abs()),
+            Some((&1, &5))
+        );
+        assert_eq!(skiplist.max_by_value_key(|v| -v), Some((&4, &1)));
+    }
+
+    #[test]
+    fn count_distinct_values() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.count_distinct_values(), 0);
+
+        for (i, label) in ["a", "b", "a", "c", "b", "a"].into_iter().enumerate() {
+            skiplist.insert(i, label);
+        }
+        assert_eq!(skiplist.count_distinct_values(), 3);
+
+        skiplist.retain_values(|v| *v != "c");
+        assert_eq!(skiplist.count_distinct_values(), 2);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut skiplist = SkipList::new();
+        for i in [3, 1, 2] {
+            skiplist.insert(i, i.to_string());
+        }
+
+        let entries = skiplist.snapshot();
+        assert_eq!(
+            entries,
+            vec![
+                (1, "1".to_string()),
+                (2, "2".to_string()),
+                (3, "3".to_string())
+            ]
+        );
+
+        let keys = std::thread::spawn(move || {
+            let rebuilt = SkipList::from_sorted(entries);
+            rebuilt.iter().map(|(k, _)| *k).collect::<Vec<_>>()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(keys, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn clone_is_structural() {
+        fn clone_unordered<K: Clone, V: Clone>(skiplist: &SkipList<K, V>) -> SkipList<K, V> {
+            skiplist.clone()
+        }
+
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i, i.to_string());
+        }
+        skiplist.set_max_level(5);
+
+        let mut cloned = clone_unordered(&skiplist);
+        assert!(cloned.structural_eq(&skiplist));
+        cloned.assert_valid();
+        assert_eq!(cloned.max_level, 5);
+
+        cloned.remove(&500);
+        cloned.insert(1000, String::new());
+        assert_eq!(skiplist.get(&500), Some(&"500".to_string()));
+        assert_eq!(skiplist.get(&1000), None);
+        cloned.assert_valid();
+
+        let empty: SkipList<i32, i32> = SkipList::new();
+        empty.clone().assert_valid();
+    }
+
+    #[test]
+    fn clone_to_vec() {
+        let mut skiplist = SkipList::new();
+        assert!(skiplist.clone_to_vec().is_empty());
+
+        for i in (0..100).rev() {
+            skiplist.insert(i, i * 2);
+        }
+
+        let entries = skiplist.clone_to_vec();
+        assert_eq!(entries.capacity(), 100);
+        assert!(entries.into_iter().eq((0..100).map(|i| (i, i * 2))));
+    }
+
+    #[test]
+    fn collect_btree() {
+        let mut skiplist = SkipList::new();
+        for i in [5, 3, 9, 1] {
+            skiplist.insert(i, i.to_string());
+        }
+
+        let map = skiplist.collect_btree();
+        assert_eq!(map.len(), 4);
+        assert!(map.iter().eq(skiplist.iter()));
+    }
+
+    #[test]
+    fn get_batch() {
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i * 2, i);
+        }
+
+        let keys: Vec<_> = (-10..2010).collect();
+        let refs: Vec<_> = keys.iter().collect();
+        let values = skiplist.get_batch(&refs);
+        for (key, value) in keys.iter().zip(values) {
+            assert_eq!(value, skiplist.get(key));
+        }
+
+        let keys = [500, 3, 1998, 0, 0, 7, 4];
+        let refs: Vec<_> = keys.iter().collect();
+        assert_eq!(
+            skiplist.get_batch(&refs),
+            vec![
+                Some(&250),
+                None,
+                Some(&999),
+                Some(&0),
+                Some(&0),
+                None,
+                Some(&2)
+            ]
+        );
+
+        let empty: SkipList<i32, i32> = SkipList::new();
+        assert_eq!(empty.get_batch(&[&1, &2]), vec![None, None]);
+    }
+
+    #[test]
+    fn count_matching() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.count_matching(|_, _| true), 0);
+
+        for i in 0..100 {
+            skiplist.insert(i, i * 3);
+        }
+        assert_eq!(skiplist.count_matching(|k, _| k % 2 == 0), 50);
+        assert_eq!(skiplist.count_matching(|_, v| *v >= 150), 50);
+        assert_eq!(skiplist.count_matching(|_, _| false), 0);
+    }
+
+    #[test]
+    fn find() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i * 3);
+        }
+
+        let mut visited = 0;
+        let found = skiplist.find(|_, v| {
+            visited += 1;
+            *v > 40
+        });
+        assert_eq!(found, Some((&14, &42)));
+        assert_eq!(visited, 15);
+
+        assert_eq!(skiplist.find(|k, _| *k > 100), None);
+    }
+
+    #[test]
+    fn compare_and_swap() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, "a");
+
+        assert_eq!(skiplist.compare_and_swap(&1, &"a", "b"), Ok(()));
+        assert_eq!(skiplist.compare_and_swap(&1, &"a", "c"), Err(Some("b")));
+        assert_eq!(skiplist.compare_and_swap(&2, &"a", "c"), Err(None));
+
+        assert_eq!(skiplist.get(&1), Some(&"b"));
+        assert_eq!(skiplist.get(&2), None);
+    }
+
+    #[test]
+    fn get2_mut() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 10);
+        skiplist.insert(2, 20);
+
+        let (a, b) = skiplist.get2_mut(&1, &2).unwrap();
+        *a += 1;
+        *b += 2;
+        assert_eq!(skiplist.get(&1), Some(&11));
+        assert_eq!(skiplist.get(&2), Some(&22));
+
+        assert!(skiplist.get2_mut(&1, &1).is_none());
+        assert!(skiplist.get2_mut(&1, &3).is_none());
+        assert!(skiplist.get2_mut(&3, &1).is_none());
+    }
+
+    #[test]
+    fn swap_values() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, "a");
+        skiplist.insert(2, "b");
+
+        assert!(skiplist.swap_values(&1, &2));
+        assert_eq!(skiplist.get(&1), Some(&"b"));
+        assert_eq!(skiplist.get(&2), Some(&"a"));
+
+        assert!(skiplist.swap_values(&1, &1));
+        assert_eq!(skiplist.get(&1), Some(&"b"));
+
+        assert!(!skiplist.swap_values(&1, &3));
+        assert_eq!(skiplist.get(&1), Some(&"b"));
+    }
+
+    #[test]
+    fn structural_eq() {
+        let skiplist = SkipList::from_sorted((0..100).map(|i| (i, i)));
+        assert!(skiplist.structural_eq(&skiplist));
+
+        let mut other = skiplist.clone();
+        other.set_max_level(1);
+        assert!(skiplist.iter().eq(other.iter()));
+        assert!(!skiplist.structural_eq(&other));
+
+        let mut shorter = skiplist.clone();
+        shorter.set_max_level(1);
+        assert!(other.structural_eq(&shorter));
+        shorter.remove(&50);
+        assert!(!other.structural_eq(&shorter));
+    }
+
+    #[test]
+    fn keys_eq() {
+        let skiplist = SkipList::from_sorted((0..100).map(|i| (i, i)));
+        let mut other = SkipList::from_sorted((0..100).map(|i| (i, -i)));
+        assert!(skiplist.keys_eq(&other));
+
+        other.remove(&50);
+        assert!(!skiplist.keys_eq(&other));
+        other.insert(100, 0);
+        assert!(!skiplist.keys_eq(&other));
+        assert!(SkipList::<i32, i32>::new().keys_eq(&SkipList::new()));
+    }
+
+    #[test]
+    fn update_value() {
+        let mut skiplist = SkipList::new();
+
+        skiplist.insert(1, "value1");
+        assert_eq!(skiplist.insert(1, "value2"), Some("value1"));
+        assert_eq!(skiplist.get(&1), Some(&"value2"));
+    }
+
+    #[test]
+    fn checked_insert() {
+        let mut skiplist = SkipList::with_capacity_limit(2);
+
+        assert_eq!(skiplist.checked_insert(1, "a"), Ok(None));
+        assert_eq!(skiplist.checked_insert(2, "b"), Ok(None));
+        assert_eq!(skiplist.checked_insert(3, "c"), Err((3, "c")));
+        assert_eq!(skiplist.checked_insert(2, "B"), Ok(Some("b")));
+        assert_eq!(skiplist.len(), 2);
+
+        skiplist.remove(&1);
+        assert_eq!(skiplist.checked_insert(3, "c"), Ok(None));
+        assert_eq!(skiplist.get(&3), Some(&"c"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut skiplist = SkipList::new();
+
+        skiplist.insert(1, "value1");
+        skiplist.insert(2, "value2");
+        skiplist.insert(3, "value3");
+
+        assert_eq!(skiplist.remove(&2), Some("value2"));
+        assert_eq!(skiplist.get(&2), None);
+        assert_eq!(skiplist.len(), 2);
+    }
+
+    #[test]
+    fn remove_if() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 10);
+        skiplist.insert(2, 20);
+
+        assert_eq!(skiplist.remove_if(&1, |_, v| *v > 10), None);
+        assert_eq!(skiplist.get(&1), Some(&10));
+        assert_eq!(skiplist.remove_if(&2, |_, v| *v > 10), Some((2, 20)));
+        assert_eq!(skiplist.remove_if(&3, |_, _| true), None);
+        assert_eq!(skiplist.len(), 1);
+    }
+
+    #[test]
+    fn remove_sorted_batch() {
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+
+        let keys: Vec<_> = (0..1000)
+            .filter(|k| k % 3 == 0)
+            .chain([1000, 2000])
+            .collect();
+        let refs: Vec<_> = keys.iter().collect();
+        assert_eq!(skiplist.remove_sorted_batch(&refs), 334);
+        assert_eq!(skiplist.len(), 666);
+        assert!(skiplist.iter().all(|(k, _)| k % 3 != 0));
+
+        let keys = [500, 1, 1, 998, 2];
+        let refs: Vec<_> = keys.iter().collect();
+        assert_eq!(skiplist.remove_sorted_batch(&refs), 4);
+        assert_eq!(skiplist.len(), 662);
+        for key in keys {
+            assert_eq!(skiplist.get(&key), None);
+        }
+        assert_eq!(skiplist.get(&997), Some(&997));
+    }
+
+    #[test]
+    fn upsert_many() {
+        let mut skiplist = SkipList::new();
+        for i in (0..100).step_by(2) {
+            skiplist.insert(i, 1);
+        }
+
+        skiplist.upsert_many((0..100).map(|i| (i, 10)), |old, new| *old += new);
+        assert_eq!(skiplist.len(), 100);
+        assert_eq!(skiplist.get(&0), Some(&11));
+        assert_eq!(skiplist.get(&1), Some(&10));
+        skiplist.assert_valid();
+
+        skiplist.upsert_many([(500, 1), (3, 100), (500, 1), (-1, 0)], |old, new| {
+            *old += new
+        });
+        assert_eq!(skiplist.len(), 102);
+        assert_eq!(skiplist.get(&3), Some(&110));
+        assert_eq!(skiplist.get(&500), Some(&2));
+        assert_eq!(skiplist.first_key(), Some(&-1));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn ensure_keys() {
+        let mut skiplist = SkipList::new();
+        for i in (0..10).step_by(3) {
+            skiplist.insert(i, 1);
+        }
+
+        skiplist.ensure_keys(0..10);
+        assert_eq!(skiplist.len(), 10);
+        assert!(skiplist
+            .iter()
+            .map(|(_, v)| *v)
+            .eq([1, 0, 0, 1, 0, 0, 1, 0, 0, 1]));
+        skiplist.assert_valid();
+
+        skiplist.ensure_keys([20, 5, 20, -1]);
+        assert_eq!(skiplist.len(), 12);
+        assert_eq!(skiplist.key_range(), Some((&-1, &20)));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "remove_front: a forward pointer still points at the removed node.")]
+    #[cfg(debug_assertions)]
+    fn remove_front_dangling_forward() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            skiplist.insert(i, i);
+        }
+        skiplist.set_max_level(1);
+        let first = skiplist.head.forward[0];
+        skiplist.head.forward.push(first);
+
+        skiplist.pop_first();
+    }
+
+    #[test]
+    #[should_panic(expected = "remove: a forward pointer still points at the removed node.")]
+    #[cfg(debug_assertions)]
+    fn remove_dangling_forward() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            skiplist.insert(i, i);
+        }
+        skiplist.set_max_level(1);
+        // Level 1 skips past the first node, but level 2 still reaches it.
+        let first = skiplist.head.forward[0];
+        let fifth = SkipList::<_, _>::get_node_ptr(&skiplist.head, &5);
+        skiplist.head.forward.extend([fifth, first]);
+
+        skiplist.remove(&0);
+    }
+
+    /// A float key whose `Ord` claims `NaN` is less than everything, itself included.
+    #[derive(Debug, PartialEq)]
+    struct LossyFloat(f64);
+
+    impl Eq for LossyFloat {}
+
+    impl PartialOrd for LossyFloat {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for LossyFloat {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0
+                .partial_cmp(&other.0)
+                .unwrap_or(std::cmp::Ordering::Less)
+        }
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            assert_eq!(skiplist.try_insert(LossyFloat(i as f64), i), Ok(None));
+        }
+        assert_eq!(skiplist.try_insert(LossyFloat(3.0), 30), Ok(Some(3)));
+
+        assert_eq!(
+            skiplist.try_insert(LossyFloat(f64::NAN), 100),
+            Err(KeyError::Incomparable)
+        );
+        assert_eq!(
+            KeyError::Incomparable.to_string(),
+            "key does not compare consistently"
+        );
+        assert_eq!(skiplist.len(), 10);
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn insert_full() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.insert_full(10, 'a'), (0, None));
+        assert_eq!(skiplist.insert_full(30, 'b'), (1, None));
+        assert_eq!(skiplist.insert_full(20, 'c'), (1, None));
+        assert_eq!(skiplist.insert_full(0, 'd'), (0, None));
+        assert_eq!(skiplist.insert_full(30, 'e'), (3, Some('b')));
+
+        assert_eq!(skiplist.len(), 4);
+        assert_eq!(skiplist.get_index(3), Some((&30, &'e')));
+    }
+
+    #[test]
+    #[should_panic(expected = "insert: the key does not compare consistently with its neighbors.")]
+    #[cfg(debug_assertions)]
+    fn insert_incomparable() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(LossyFloat(1.0), 1);
+        skiplist.insert(LossyFloat(f64::NAN), 2);
+    }
+
+    #[test]
+    fn remove_non_existent() {
+        let mut skiplist = SkipList::new();
+
+        skiplist.insert(1, "value1");
+        assert_eq!(skiplist.remove(&2), None);
+    }
+
+    #[test]
+    fn search() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.search(&1), Err(0));
+
+        for i in 0..10 {
+            skiplist.insert(i * 2, i);
+        }
+
+        assert_eq!(skiplist.search(&0), Ok(0));
+        assert_eq!(skiplist.search(&8), Ok(4));
+        assert_eq!(skiplist.search(&9), Err(5));
+        assert_eq!(skiplist.search(&-1), Err(0));
+        assert_eq!(skiplist.search(&18), Ok(9));
+        assert_eq!(skiplist.search(&100), Err(10));
+    }
+
+    #[test]
+    fn first_and_last_key() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.first_key(), None);
+        assert_eq!(skiplist.last_key(), None);
+
+        for i in [5, 3, 9, 1, 7] {
+            skiplist.insert(i, i);
+        }
+        assert_eq!(skiplist.first_key(), Some(&1));
+        assert_eq!(skiplist.last_key(), Some(&9));
+
+        skiplist.remove(&1);
+        skiplist.remove(&9);
+        assert_eq!(skiplist.first_key(), Some(&3));
+        assert_eq!(skiplist.last_key(), Some(&7));
+    }
+
+    #[test]
+    fn key_range() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.key_range(), None);
+
+        skiplist.insert(4, 4);
+        assert_eq!(skiplist.key_range(), Some((&4, &4)));
+
+        for i in [5, 3, 9, 1, 7] {
+            skiplist.insert(i, i);
+        }
+        assert_eq!(skiplist.key_range(), Some((&1, &9)));
+    }
+
+    #[test]
+    fn value_at_or_before() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.value_at_or_before(&0), None);
+
+        for (t, v) in [(10, "a"), (20, "b"), (30, "c")] {
+            skiplist.insert(t, v);
+        }
+
+        assert_eq!(skiplist.value_at_or_before(&5), None);
+        assert_eq!(skiplist.value_at_or_before(&10), Some(&"a"));
+        assert_eq!(skiplist.value_at_or_before(&19), Some(&"a"));
+        assert_eq!(skiplist.value_at_or_before(&20), Some(&"b"));
+        assert_eq!(skiplist.value_at_or_before(&1000), Some(&"c"));
+    }
+
+    #[test]
+    fn get_index() {
+        let mut skiplist = SkipList::new();
+        for i in [5, 3, 9, 1, 7] {
+            skiplist.insert(i, i * 10);
+        }
+
+        assert_eq!(skiplist.get_index(0), Some((&1, &10)));
+        assert_eq!(skiplist.get_index(4), Some((&9, &90)));
+        assert_eq!(skiplist.get_index(5), None);
+        assert_eq!(skiplist.index_entry(2), (&5, &50));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the len is 1 but the index is 1")]
+    fn index_entry_out_of_bounds() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 1);
+        skiplist.index_entry(1);
+    }
+
+    #[test]
+    fn peek_min_and_max() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.peek_min(), None);
+        assert_eq!(skiplist.peek_max(), None);
+
+        for i in [5, 3, 9, 1, 7] {
+            skiplist.insert(i, i * 10);
+        }
+        assert_eq!(skiplist.peek_min(), Some((&1, &10)));
+        assert_eq!(skiplist.peek_max(), Some((&9, &90)));
+
+        let shared = &skiplist;
+        assert_eq!(shared.peek_min(), Some((&1, &10)));
+    }
+
+    #[test]
+    fn assert_valid() {
+        let mut skiplist = SkipList::new();
+        skiplist.assert_valid();
+
+        for i in (0..500).rev() {
+            skiplist.insert(i * 7 % 500, i);
+            skiplist.assert_valid();
+        }
+        for i in (0..500).step_by(3) {
+            skiplist.remove(&i);
+        }
+        skiplist.drain_range(100..200).for_each(drop);
+        skiplist.retain_largest(200);
+        skiplist.shrink_to_fit();
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "assert_valid: `len` is out of sync.")]
+    fn assert_valid_len() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 1);
+        skiplist.len = 2;
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "assert_valid: keys are not strictly increasing.")]
+    fn assert_valid_order() {
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 1);
+        skiplist.insert(2, 2);
+        skiplist
+            .head
+            .next
+            .as_mut()
+            .unwrap()
+            .data
+            .as_mut()
+            .unwrap()
+            .key = 3;
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn memory_report() {
+        let slot_size = std::mem::size_of::<usize>();
+        let mut skiplist: SkipList<u64, u64> = SkipList::new();
+        let report = skiplist.memory_report();
+        assert_eq!(report.node_count, 0);
+        assert_eq!(report.forward_bytes, 0);
+        assert_eq!(report.payload_bytes, 0);
+        assert!(report.head_overhead_bytes >= MAX_LEVEL * slot_size);
+
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+        skiplist.shrink_to_fit();
+
+        let report = skiplist.memory_report();
+        assert_eq!(report.node_count, 100);
+        let slots: usize = (1..)
+            .zip(skiplist.level_histogram())
+            .map(|(level, count)| level * count)
+            .sum();
+        assert_eq!(report.total_forward_slots, slots);
+        assert_eq!(report.forward_bytes, slots * slot_size);
+        assert_eq!(report.payload_bytes, 1600);
+    }
+
+    #[test]
+    fn count_nodes() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.count_nodes(), 0);
+
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+        skiplist.retain_keys(|k| k % 2 == 0);
+        skiplist.truncate(40);
+        skiplist.remove(&10);
+        skiplist.drain_range(20..30).for_each(drop);
+        skiplist.pop_last();
+
+        assert_eq!(skiplist.count_nodes(), skiplist.len());
+        assert_eq!(skiplist.len(), 33);
+    }
+
+    #[test]
+    fn clear() {
+        let mut skiplist = SkipList::new();
+
+        skiplist.insert(1, "value1");
+        skiplist.insert(2, "value2");
+        skiplist.clear();
+
+        assert_eq!(skiplist.len(), 0);
+        assert!(skiplist.is_empty());
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+        skiplist.head.next.as_mut().unwrap().forward.reserve(100);
+
+        skiplist.shrink_to_fit();
+        assert_eq!(skiplist.head.forward.capacity(), skiplist.head.level());
+        assert_eq!(skiplist.head.level(), skiplist.level_histogram().len());
+        let first = skiplist.head.next.as_ref().unwrap();
+        assert_eq!(first.forward.capacity(), first.level());
+
+        for i in 0..100 {
+            assert_eq!(skiplist.get(&i), Some(&i));
+        }
+        for i in 100..200 {
+            skiplist.insert(i, i);
+        }
+        assert_eq!(skiplist.len(), 200);
+        assert_eq!(skiplist.count_nodes(), 200);
+
+        skiplist.clear();
+        skiplist.shrink_to_fit();
+        assert_eq!(skiplist.head.level(), 0);
+        skiplist.insert(1, 1);
+        assert_eq!(skiplist.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn clear_and_shrink() {
+        let mut skiplist = SkipList::new();
+
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+        skiplist.clear_and_shrink();
+
+        assert!(skiplist.is_empty());
+        assert_eq!(skiplist.head.forward.capacity(), 0);
+        assert_eq!(skiplist.get(&1), None);
+        assert_eq!(skiplist.remove(&1), None);
+
+        for i in 0..100 {
+            skiplist.insert(i, i * 2);
+        }
+        for i in 0..100 {
+            assert_eq!(skiplist.get(&i), Some(&(i * 2)));
+        }
+        for i in (0..100).step_by(2) {
+            assert_eq!(skiplist.remove(&i), Some(i * 2));
+        }
+        assert_eq!(skiplist.len(), 50);
+        assert_eq!(skiplist.iter().count(), 50);
+    }
+
+    #[test]
+    fn append_disjoint() {
+        let mut skiplist = SkipList::from_sorted((0..100).map(|i| (i, i)));
+        let other = SkipList::from_sorted((100..300).map(|i| (i, i)));
+
+        skiplist.append_disjoint(other);
+        assert_eq!(skiplist.len(), 300);
+        assert!(skiplist.iter().map(|(k, _)| *k).eq(0..300));
+        for i in 0..300 {
+            assert_eq!(skiplist.get(&i), Some(&i));
+        }
+
+        skiplist.insert(300, 300);
+        assert_eq!(skiplist.remove(&150), Some(150));
+        assert_eq!(skiplist.last_key(), Some(&300));
+
+        let mut empty = SkipList::new();
+        empty.clear_and_shrink();
+        empty.append_disjoint(skiplist);
+        assert_eq!(empty.len(), 300);
+        assert_eq!(empty.get(&299), Some(&299));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "append_disjoint")]
+    fn append_disjoint_overlapping() {
+        let mut skiplist = SkipList::from_sorted((0..10).map(|i| (i, i)));
+        let other = SkipList::from_sorted((5..15).map(|i| (i, i)));
+
+        skiplist.append_disjoint(other);
+    }
+
+    #[test]
+    fn retain() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+
+        skiplist.retain(|k, v| {
+            *v *= 10;
+            k % 3 != 0
+        });
+        assert_eq!(skiplist.len(), 66);
+        assert_eq!(skiplist.get(&3), None);
+        assert_eq!(skiplist.get(&4), Some(&40));
+
+        skiplist.retain_keys(|k| *k < 50);
+        assert_eq!(skiplist.len(), 33);
+        assert_eq!(skiplist.iter().count(), 33);
+        assert_eq!(skiplist.last_key(), Some(&49));
+
+        skiplist.insert(60, 0);
+        assert_eq!(skiplist.get(&60), Some(&0));
+    }
+
+    #[test]
+    fn retain_values() {
+        let mut skiplist = SkipList::new();
+        for i in 0..20 {
+            skiplist.insert(i, i % 4);
+        }
+
+        skiplist.retain_values(|v| {
+            *v *= 10;
+            *v != 0
+        });
+        assert_eq!(skiplist.len(), 15);
+        assert_eq!(skiplist.get(&4), None);
+        assert_eq!(skiplist.get(&7), Some(&30));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn retain_count() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+
+        assert_eq!(skiplist.retain_count(|k, _| k % 4 != 0), 25);
+        assert_eq!(skiplist.len(), 75);
+        assert_eq!(skiplist.retain_count(|_, _| true), 0);
+        assert_eq!(skiplist.retain_count(|_, _| false), 75);
+        assert!(skiplist.is_empty());
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn extend_sorted() {
+        let mut skiplist = SkipList::new();
+        skiplist.clear_and_shrink();
+        skiplist.extend_sorted((0..100).map(|i| (i, i)));
+        skiplist.extend_sorted((100..1000).map(|i| (i, i)));
+        skiplist.extend_sorted(std::iter::empty());
+
+        assert_eq!(skiplist.len(), 1000);
+        skiplist.assert_valid();
+        assert_eq!(skiplist.get(&500), Some(&500));
+        assert_eq!(skiplist.remove(&999), Some(999));
+        skiplist.extend_sorted([(999, 0)]);
+        assert_eq!(skiplist.last_key(), Some(&999));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    #[cfg(debug_assertions)]
+    fn extend_sorted_overlapping() {
+        let mut skiplist = SkipList::from_sorted((0..10).map(|i| (i, i)));
+        skiplist.extend_sorted([(5, 5)]);
+    }
+
+    #[test]
+    fn drain_into() {
+        let mut dest = SkipList::new();
+        let mut src = SkipList::from_sorted((0..50).map(|i| (i, i)));
+
+        src.drain_into(&mut dest);
+        assert!(src.is_empty());
+        assert_eq!(src.iter().count(), 0);
+        assert_eq!(dest.len(), 50);
+
+        let mut src = SkipList::from_sorted((50..100).map(|i| (i, i)));
+        src.drain_into(&mut dest);
+        assert_eq!(dest.len(), 100);
+        assert!(dest.iter().map(|(k, _)| *k).eq(0..100));
+
+        let mut src = SkipList::from_sorted((90..110).map(|i| (i, -i)));
+        src.drain_into(&mut dest);
+        assert!(src.is_empty());
+        assert_eq!(dest.len(), 110);
+        assert_eq!(dest.get(&89), Some(&89));
+        assert_eq!(dest.get(&95), Some(&-95));
+        assert_eq!(dest.get(&109), Some(&-109));
+
+        src.insert(1, 1);
+        assert_eq!(src.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn drain_into_respects_max_level() {
+        let mut dest = SkipList::new();
+        dest.set_max_level(2);
+        dest.insert(-1, -1);
+
+        let mut src = SkipList::from_sorted((0..1000).map(|i| (i, i)));
+        assert!(src.level_histogram().len() > 2);
+        src.drain_into(&mut dest);
+
+        assert_eq!(dest.len(), 1001);
+        assert!(dest.level_histogram().len() <= 2);
+        assert!(dest.head.level() <= 2);
+        dest.assert_valid();
+
+        dest.insert(2000, 0);
+        assert!(dest.height_of(&2000).unwrap() < 2);
+    }
+
+    #[test]
+    fn retain_indexed() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            skiplist.insert(i * 10, i);
+        }
+
+        let mut seen = Vec::new();
+        skiplist.retain_indexed(|index, k, _| {
+            seen.push((index, *k));
+            index % 2 == 0
+        });
+
+        assert_eq!(seen, (0..10).map(|i| (i, i * 10)).collect::<Vec<_>>());
+        assert!(skiplist.iter().map(|(k, _)| *k).eq([0, 20, 40, 60, 80]));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn dedup_values() {
+        let mut skiplist = SkipList::new();
+        for (i, v) in [1, 1, 2, 2, 2, 1, 3, 3].into_iter().enumerate() {
+            skiplist.insert(i, v);
+        }
+
+        skiplist.dedup_values();
+        assert_eq!(skiplist.len(), 4);
+        assert_eq!(
+            skiplist.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![(0, 1), (2, 2), (5, 1), (6, 3)]
+        );
+        for i in [1, 3, 4, 7] {
+            assert_eq!(skiplist.get(&i), None);
+        }
+        assert_eq!(skiplist.get(&6), Some(&3));
+        assert_eq!(skiplist.last_key(), Some(&6));
+
+        skiplist.insert(7, 3);
+        assert_eq!(skiplist.remove(&5), Some(1));
+        assert_eq!(skiplist.len(), 4);
+    }
+
+    #[test]
+    fn retain_smallest_and_largest() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i);
+        }
+
+        skiplist.retain_smallest(200);
+        skiplist.retain_largest(100);
+        assert_eq!(skiplist.len(), 100);
+
+        skiplist.retain_smallest(60);
+        assert!(skiplist.iter().map(|(k, _)| *k).eq(0..60));
+
+        skiplist.retain_largest(20);
+        assert_eq!(skiplist.len(), 20);
+        assert!(skiplist.iter().map(|(k, _)| *k).eq(40..60));
+        assert_eq!(skiplist.get(&39), None);
+        assert_eq!(skiplist.get(&40), Some(&40));
+
+        skiplist.insert(0, 0);
+        assert_eq!(skiplist.first_key(), Some(&0));
+
+        skiplist.retain_largest(0);
+        assert!(skiplist.is_empty());
+        assert_eq!(skiplist.iter().count(), 0);
+    }
+
+    #[test]
+    fn map_keys_monotonic() {
+        let skiplist = SkipList::from_sorted((0..100).map(|i| (i, i)));
+
+        let shifted = skiplist.map_keys_monotonic(|k| k as i64 * 2 + 1);
+        assert!(shifted
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .eq((0..100).map(|i| (i as i64 * 2 + 1, i))));
+
+        let mut negated = shifted.map_keys_monotonic(|k| -k);
+        assert_eq!(negated.len(), 100);
+        assert_eq!(negated.first_key(), Some(&-199));
+        assert_eq!(negated.get(&-1), Some(&0));
+        assert!(negated
+            .iter()
+            .map(|(k, _)| *k)
+            .eq((0..100).rev().map(|i| -(i * 2 + 1))));
+        negated.insert(0, 0);
+        assert_eq!(negated.last_key(), Some(&0));
+
+        let single = SkipList::from_sorted([(1, 1)]).map_keys_monotonic(|k| -k);
+        assert_eq!(single.get(&-1), Some(&1));
+    }
+
+    #[test]
+    fn map_keys_monotonic_keeps_towers() {
+        let mut skiplist = SkipList::new();
+        skiplist.set_max_level(4);
+        for i in 0..200 {
+            skiplist.insert(i, i);
+        }
+        let heights: Vec<_> = (0..200).map(|i| skiplist.height_of(&i)).collect();
+
+        let shifted = skiplist.map_keys_monotonic(|k| k + 1000);
+        assert_eq!(shifted.max_level, 4);
+        assert!((0..200)
+            .map(|i| shifted.height_of(&(i + 1000)))
+            .eq(heights.clone()));
+        shifted.assert_valid();
+
+        let negated = shifted.map_keys_monotonic(|k| -k);
+        assert!((0..200)
+            .map(|i| negated.height_of(&-(i + 1000)))
+            .eq(heights));
+        negated.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    #[cfg(debug_assertions)]
+    fn map_keys_monotonic_not_monotonic() {
+        let skiplist = SkipList::from_sorted((0..10).map(|i| (i, i)));
+        skiplist.map_keys_monotonic(|k| (k - 5) * (k - 5));
+    }
+
+    #[test]
+    fn split_into() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            skiplist.insert(i, i);
+        }
+
+        let parts = skiplist.split_into(3);
+        let keys: Vec<Vec<_>> = parts
+            .iter()
+            .map(|part| part.iter().map(|(k, _)| *k).collect())
+            .collect();
+        assert_eq!(keys, vec![vec![0, 1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+        assert_eq!(parts[1].get(&5), Some(&5));
+        assert_eq!(parts[1].len(), 3);
+
+        let mut skiplist = SkipList::new();
+        skiplist.insert(1, 1);
+        let lens: Vec<_> = skiplist.split_into(3).iter().map(|p| p.len()).collect();
+        assert_eq!(lens, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn dedup_by() {
+        let mut skiplist = SkipList::new();
+        for k in [10, 11, 12, 20, 25, 30, 31] {
+            skiplist.insert(k, k * 2);
+        }
+
+        skiplist.dedup_by(|prev_k, _, k, _| k / 10 == prev_k / 10);
+        assert_eq!(skiplist.len(), 3);
+        assert_eq!(
+            skiplist.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![10, 20, 30]
+        );
+        assert_eq!(skiplist.get(&25), None);
+        assert_eq!(skiplist.get(&30), Some(&60));
+
+        skiplist.dedup_by(|_, prev_v, _, v| v - prev_v <= 20);
+        assert_eq!(
+            skiplist.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![10, 30]
+        );
+    }
+
+    #[test]
+    fn pop_index() {
+        let mut skiplist = SkipList::new();
+        for i in 0..10 {
+            skiplist.insert(i, i * 10);
+        }
+
+        assert_eq!(skiplist.pop_index(3), Some((3, 30)));
+        assert_eq!(skiplist.pop_index(3), Some((4, 40)));
+        assert_eq!(skiplist.pop_index(8), None);
+        assert_eq!(skiplist.pop_first(), Some((0, 0)));
+        assert_eq!(skiplist.pop_last(), Some((9, 90)));
+        assert_eq!(skiplist.pop_index(4), Some((7, 70)));
+
+        assert_eq!(skiplist.len(), 5);
+        assert!(skiplist.iter().map(|(k, _)| *k).eq([1, 2, 5, 6, 8]));
+        assert_eq!(skiplist.get(&5), Some(&50));
+
+        let mut empty: SkipList<i32, i32> = SkipList::new();
+        assert_eq!(empty.pop_first(), None);
+        assert_eq!(empty.pop_last(), None);
+        assert_eq!(empty.pop_index(0), None);
+    }
+
+    #[test]
+    fn pop_last_drains() {
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+
+        for i in (500..1000).rev() {
+            assert_eq!(skiplist.pop_last(), Some((i, i)));
+        }
+        skiplist.assert_valid();
+        assert_eq!(skiplist.last_key(), Some(&499));
+
+        while skiplist.pop_last().is_some() {}
+        assert!(skiplist.is_empty());
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn pop_front_if() {
+        let mut skiplist = SkipList::new();
+        assert_eq!(skiplist.pop_front_if(|_, _| true), None);
+
+        for i in [30, 10, 20] {
+            skiplist.insert(i, i * 2);
+        }
+
+        assert_eq!(skiplist.pop_front_if(|k, _| *k <= 15), Some((10, 20)));
+        assert_eq!(skiplist.pop_front_if(|k, _| *k <= 15), None);
+        assert_eq!(skiplist.len(), 2);
+        assert_eq!(skiplist.pop_front_if(|_, v| *v == 40), Some((20, 40)));
+        assert_eq!(skiplist.first_key(), Some(&30));
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn split_off_index_vec() {
+        let mut skiplist = SkipList::new();
+        for i in 0..100 {
+            skiplist.insert(i, i * 2);
+        }
+
+        assert_eq!(skiplist.split_off_index_vec(100), vec![]);
+        let tail = skiplist.split_off_index_vec(95);
+        assert_eq!(tail, (95..100).map(|i| (i, i * 2)).collect::<Vec<_>>());
+        assert_eq!(tail.capacity(), 5);
+        assert_eq!(skiplist.len(), 95);
+        assert_eq!(skiplist.last_key(), Some(&94));
+        skiplist.assert_valid();
+
+        assert_eq!(skiplist.split_off_index_vec(0).len(), 95);
+        assert!(skiplist.is_empty());
+        skiplist.assert_valid();
+    }
+
+    #[test]
+    fn truncate() {
+        let mut skiplist = SkipList::new();
+
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+
+        skiplist.truncate(2000);
+        assert_eq!(skiplist.len(), 1000);
+
+        skiplist.truncate(100);
+        assert_eq!(skiplist.len(), 100);
+        assert_eq!(skiplist.iter().count(), 100);
+        assert_eq!(skiplist.last_key(), Some(&99));
+        assert_eq!(skiplist.get(&100), None);
+
+        for i in 100..200 {
+            skiplist.insert(i, i);
+        }
+        assert_eq!(skiplist.len(), 200);
+        assert_eq!(skiplist.get(&150), Some(&150));
+
+        skiplist.truncate(0);
+        assert!(skiplist.is_empty());
+        assert_eq!(skiplist.first_key(), None);
+        assert_eq!(skiplist.last_key(), None);
+    }
+
+    #[test]
+    fn set_max_level() {
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+
+        skiplist.set_max_level(2);
+        assert!(skiplist.head.level() <= 2);
+        assert!(skiplist.iter().count() == 1000);
+        for i in 0..1000 {
+            assert_eq!(skiplist.get(&i), Some(&i));
+        }
+
+        for i in 1000..2000 {
+            skiplist.insert(i, i);
+        }
+        assert!(skiplist.head.level() <= 2);
+        for i in (0..2000).step_by(3) {
+            assert_eq!(skiplist.remove(&i), Some(i));
+        }
+        assert_eq!(skiplist.len(), 1333);
+        assert_eq!(skiplist.clone().max_level, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "max level")]
+    fn set_max_level_zero() {
+        let mut skiplist: SkipList<i32, i32> = SkipList::new();
+        skiplist.set_max_level(0);
+    }
+
+    #[test]
+    fn reserve_levels() {
+        let mut skiplist = SkipList::new();
+        skiplist.clear_and_shrink();
+
+        skiplist.reserve_levels(1000);
+        assert!(skiplist.head.level() > 1);
+        assert!(skiplist.is_empty());
+
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+        assert_eq!(skiplist.get(&500), Some(&500));
+    }
+
+    #[test]
+    fn rebuild_with_seed() {
+        let mut a = SkipList::new();
+        let mut b = SkipList::new();
+        for i in 0..1000 {
+            a.insert(i, i * 2);
+            b.insert(999 - i, (999 - i) * 2);
+        }
+
+        a.rebuild_with_seed(42);
+        b.rebuild_with_seed(42);
+        assert!(a.structural_eq(&b));
+        assert_eq!(a.level_histogram(), b.level_histogram());
+        assert_eq!(a.level_histogram().iter().sum::<usize>(), 1000);
+
+        assert_eq!(a.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(a.get(&i), Some(&(i * 2)));
+        }
+        assert_eq!(a.remove(&500), Some(1000));
+        a.insert(500, 0);
+        assert_eq!(a.get(&500), Some(&0));
+
+        a.set_max_level(2);
+        a.rebuild_with_seed(7);
+        assert!(a.level_histogram().len() <= 2);
+        assert!(SkipList::<i32, i32>::new().level_histogram().is_empty());
+    }
+
+    #[test]
+    fn height_of() {
+        let mut skiplist = SkipList::new();
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+
+        let mut histogram = vec![0; skiplist.level_histogram().len()];
+        for i in 0..1000 {
+            let height = skiplist.height_of(&i).unwrap();
+            histogram[height] += 1;
+        }
+        assert_eq!(histogram, skiplist.level_histogram());
+        assert_eq!(skiplist.height_of(&1000), None);
+
+        skiplist.set_max_level(1);
+        assert_eq!(skiplist.height_of(&500), Some(0));
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut skiplist = SkipList::with_capacity(1000);
+        assert_eq!(skiplist.head.level(), expected_level(1000));
+        assert!(skiplist.is_empty());
+
+        for i in 0..1000 {
+            skiplist.insert(i, i);
+        }
+        for i in 0..1000 {
+            assert_eq!(skiplist.get(&i), Some(&i));
+        }
+
+        let skiplist: SkipList<i32, i32> = SkipList::with_capacity(0);
+        assert_eq!(skiplist.head.level(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "doubly-linked")]
+    fn back_links() {
+        fn assert_back_links(skiplist: &SkipList<i32, i32>) {
+            let mut prev = None;
+            let mut cur = skiplist.head.next.as_deref();
+            while let Some(node) = cur {
+                assert_eq!(node.prev, prev);
+                prev = Some(std::ptr::NonNull::from(node));
+                cur = node.next.as_deref();
+            }
+        }
+
+        let mut skiplist = SkipList::from_sorted((0..200).map(|i| (i * 2, i)));
+        assert_back_links(&skiplist);
+
+        for i in 0..100 {
+            skiplist.insert(i * 2 + 1, i);
+        }
+        skiplist.remove(&0);
+        skiplist.remove(&51);
+        skiplist.remove(&1000);
+        assert_back_links(&skiplist);
+
+        skiplist.retain_range(10..300);
+        skiplist.truncate(200);
+        skiplist.dedup_values();
+        assert_back_links(&skiplist);
+
+        skiplist.append_disjoint(SkipList::from_sorted((1000..1100).map(|i| (i, i))));
+        assert_back_links(&skiplist);
+
+        skiplist.clear();
+        skiplist.insert(1, 1);
+        assert_back_links(&skiplist);
     }
 
     #[test]
@@ -351,6 +3016,49 @@ mod tests {
         assert_eq!(cloned_skiplist.len(), 2);
     }
 
+    #[test]
+    fn from_sorted() {
+        let sl = SkipList::from_sorted((0..1000).map(|i| (i, i * 2)));
+
+        assert_eq!(sl.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(sl.get(&i), Some(&(i * 2)));
+        }
+        assert_eq!(sl.get(&1000), None);
+
+        let keys: Vec<_> = sl.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, (0..1000).collect::<Vec<_>>());
+
+        let mut sl = sl;
+        assert_eq!(sl.remove(&500), Some(1000));
+        sl.insert(500, 0);
+        assert_eq!(sl.get(&500), Some(&0));
+    }
+
+    #[test]
+    fn rebuild_from_sorted() {
+        let mut sl = SkipList::from_sorted((0..1000).map(|i| (i, i)));
+        let head_level = sl.head.level();
+
+        sl.rebuild_from_sorted((500..600).map(|i| (i, -i)));
+        assert_eq!(sl.len(), 100);
+        assert!(sl.head.level() >= head_level);
+        assert_eq!(sl.get(&0), None);
+        assert_eq!(sl.get(&550), Some(&-550));
+        assert!(sl.iter().map(|(k, _)| *k).eq(500..600));
+
+        sl.insert(0, 0);
+        assert_eq!(sl.first_key(), Some(&0));
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    #[cfg(debug_assertions)]
+    fn rebuild_from_sorted_unsorted() {
+        let mut sl = SkipList::new();
+        sl.rebuild_from_sorted([(2, 2), (1, 1)]);
+    }
+
     #[test]
     fn display() {
         let mut skiplist = SkipList::new();
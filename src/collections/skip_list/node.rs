@@ -6,18 +6,20 @@ pub(crate) struct Node<K, V> {
     pub(crate) data: Option<Data<K, V>>,
     pub(crate) next: Option<Box<Node<K, V>>>,
     pub(crate) forward: Vec<Option<NonNull<Node<K, V>>>>,
+    /// Level-0 back link, `None` for the first node.
+    #[cfg(feature = "doubly-linked")]
+    pub(crate) prev: Option<NonNull<Node<K, V>>>,
 }
 
-impl<K, V> Node<K, V>
-where
-    K: Ord,
-{
+impl<K, V> Node<K, V> {
     #[inline]
     pub(crate) fn new(data: Option<Data<K, V>>, level: usize) -> Self {
         Self {
             data,
             next: None,
             forward: vec![None; level],
+            #[cfg(feature = "doubly-linked")]
+            prev: None,
         }
     }
 
@@ -41,6 +43,11 @@ where
         self.data.as_ref().map(|data| &data.value)
     }
 
+    #[inline]
+    pub(crate) fn key_value(&self) -> Option<(&K, &V)> {
+        self.data.as_ref().map(|data| (&data.key, &data.value))
+    }
+
     #[inline]
     pub(crate) fn value_mut(&mut self) -> Option<&mut V> {
         self.data.as_mut().map(|data| &mut data.value)
@@ -54,26 +61,39 @@ where
     }
 
     #[inline]
-    #[allow(unused)]
     pub(crate) fn next_node_ptr(&self) -> Option<NonNull<Node<K, V>>> {
         self.next.as_ref().map(|node| NonNull::from(node.as_ref()))
     }
 
     #[inline]
     pub(crate) fn connect_next_node(&mut self, mut next: Box<Node<K, V>>) {
-        next.as_mut().next = self.next.take();
-        self.next.replace(next);
+        next.as_mut().set_next(self.next.take());
+        self.set_next(Some(next));
+    }
+
+    /// Replaces the level-0 successor, keeping its back link in step.
+    #[inline]
+    pub(crate) fn set_next(&mut self, next: Option<Box<Node<K, V>>>) {
+        #[cfg(feature = "doubly-linked")]
+        let self_ptr = self.data.as_ref().map(|_| NonNull::from(&*self));
+
+        self.next = next;
+
+        #[cfg(feature = "doubly-linked")]
+        if let Some(next) = self.next.as_mut() {
+            next.prev = self_ptr;
+        }
     }
 }
 
-impl<K, V> Into<Option<Data<K, V>>> for Node<K, V> {
-    fn into(self) -> Option<Data<K, V>> {
-        self.data
+impl<K, V> From<Node<K, V>> for Option<Data<K, V>> {
+    fn from(node: Node<K, V>) -> Self {
+        node.data
     }
 }
 
-impl<K, V> Into<Option<(K, V)>> for Node<K, V> {
-    fn into(self) -> Option<(K, V)> {
-        self.data.map(|data| data.into())
+impl<K, V> From<Node<K, V>> for Option<(K, V)> {
+    fn from(node: Node<K, V>) -> Self {
+        node.data.map(|data| data.into())
     }
 }
@@ -0,0 +1,865 @@
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+    ops::{Add, Bound, RangeBounds},
+    ptr::NonNull,
+};
+
+use super::{
+    before_lower_bound, drop_chain, gen_level::LevelGenerator, within_upper_bound, AdjustNodes,
+    Chain, Node, SkipList,
+};
+
+pub struct Range<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    current: Option<&'a Node<K, V>>,
+    end: Option<NonNull<Node<K, V>>>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.current.take()?;
+        if self.end == Some(NonNull::from(node)) {
+            return None;
+        }
+
+        self.current = node.next.as_ref().map(|node| node.as_ref());
+
+        let result = node
+            .data
+            .as_ref()
+            .map(|data| (&data.key, &data.value))
+            .expect("must have data.");
+
+        Some(result)
+    }
+}
+
+pub struct RangeMut<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    current: Option<NonNull<Node<K, V>>>,
+    end: Option<NonNull<Node<K, V>>>,
+    _marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for RangeMut<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node_ptr = self.current.take()?;
+        if self.end == Some(node_ptr) {
+            return None;
+        }
+
+        let node = unsafe { &mut *node_ptr.as_ptr() };
+        self.current = node.next.as_deref().map(NonNull::from);
+
+        let result = node
+            .data
+            .as_mut()
+            .map(|data| (&data.key, &mut data.value))
+            .expect("must have data.");
+
+        Some(result)
+    }
+}
+
+/// Entries of a key window from the largest down, see `SkipList::range_rev`.
+pub struct RangeRev<'a, K, V, G>
+where
+    K: Ord + 'a,
+    V: 'a,
+    G: LevelGenerator + 'a,
+{
+    list: &'a SkipList<K, V, G>,
+    front: Option<NonNull<Node<K, V>>>,
+    back: Option<&'a Node<K, V>>,
+}
+
+impl<'a, K, V, G> Iterator for RangeRev<'a, K, V, G>
+where
+    K: Ord + 'a,
+    V: 'a,
+    G: LevelGenerator + 'a,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.back.take()?;
+        let (key, value) = node.key_value().expect("must have data.");
+
+        if self.front != Some(NonNull::from(node)) {
+            #[cfg(not(feature = "doubly-linked"))]
+            let prev_ptr = SkipList::<K, V, G>::get_adjust_nodes(&self.list.head, key).0;
+            #[cfg(feature = "doubly-linked")]
+            let prev_ptr = node.prev.unwrap_or(NonNull::from(&self.list.head));
+
+            self.back = Some(unsafe { &*prev_ptr.as_ptr() }).filter(|node| node.data.is_some());
+        }
+
+        Some((key, value))
+    }
+}
+
+pub struct DrainRange<'a, K, V> {
+    chain: Chain<K, V>,
+    _marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+impl<K, V> Iterator for DrainRange<'_, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.chain.take()?;
+        self.chain = node.next.take();
+        node.data.take().map(|data| data.into())
+    }
+}
+
+impl<K, V> Drop for DrainRange<'_, K, V> {
+    fn drop(&mut self) {
+        drop_chain(self.chain.take());
+    }
+}
+
+/// Why `SkipList::try_range` rejected a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The start bound is greater than the end bound.
+    Inverted,
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeError::Inverted => write!(f, "range start is greater than range end"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// Integer keys that step to the next value, for `SkipList::first_gap`.
+pub trait Successor: Sized {
+    /// The next value, `None` past the largest one.
+    fn successor(self) -> Option<Self>;
+}
+
+macro_rules! impl_successor {
+    ($($t:ty),*) => {
+        $(
+            impl Successor for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_successor!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The lower bound of the part of the list past an upper bound, `None` if there is none.
+fn past_upper_bound<K>(bound: Bound<&K>) -> Option<Bound<&K>> {
+    match bound {
+        Bound::Included(k) => Some(Bound::Excluded(k)),
+        Bound::Excluded(k) => Some(Bound::Included(k)),
+        Bound::Unbounded => None,
+    }
+}
+
+impl<K, V, G> SkipList<K, V, G>
+where
+    K: Ord,
+    G: LevelGenerator,
+{
+    /// Entries whose keys fall in `range`, in key order. An inverted range yields nothing.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K, V> {
+        let start = Self::lower_bound_ptr(&self.head, range.start_bound());
+
+        let end = past_upper_bound(range.end_bound())
+            .and_then(|bound| Self::lower_bound_ptr(&self.head, bound));
+
+        let current = start.map(|ptr| unsafe { &*ptr.as_ptr() }).filter(|node| {
+            node.key()
+                .is_some_and(|k| within_upper_bound(k, range.end_bound()))
+        });
+
+        Range { current, end }
+    }
+
+    /// Like `range`, but the values are mutable.
+    pub fn range_mut<R: RangeBounds<K>>(&mut self, range: R) -> RangeMut<'_, K, V> {
+        let start = Self::lower_bound_ptr(&self.head, range.start_bound());
+
+        let end = past_upper_bound(range.end_bound())
+            .and_then(|bound| Self::lower_bound_ptr(&self.head, bound));
+
+        let current = start.filter(|ptr| {
+            unsafe { ptr.as_ref() }
+                .key()
+                .is_some_and(|k| within_upper_bound(k, range.end_bound()))
+        });
+
+        RangeMut {
+            current,
+            end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The values of the entries in `range`, in key order.
+    pub fn range_values<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = &V> {
+        self.range(range).map(|(_, value)| value)
+    }
+
+    /// Like `range_values`, but the values are mutable.
+    pub fn range_values_mut<R: RangeBounds<K>>(
+        &mut self,
+        range: R,
+    ) -> impl Iterator<Item = &mut V> {
+        self.range_mut(range).map(|(_, value)| value)
+    }
+
+    /// Applies `f` to every entry in `range`, in key order. Only values change.
+    pub fn map_range<R, F>(&mut self, range: R, mut f: F)
+    where
+        R: RangeBounds<K>,
+        F: FnMut(&K, &mut V),
+    {
+        for (key, value) in self.range_mut(range) {
+            f(key, value);
+        }
+    }
+
+    /// Like `shrink_values`, but only for the values whose keys fall in `range`, e.g. a
+    /// recently written window.
+    pub fn shrink_range_values<R, F>(&mut self, range: R, mut f: F)
+    where
+        R: RangeBounds<K>,
+        F: FnMut(&mut V),
+    {
+        for value in self.range_values_mut(range) {
+            f(value);
+        }
+    }
+
+    /// Like `range`, but an inverted range is an error instead of empty.
+    pub fn try_range<R: RangeBounds<K>>(&self, range: R) -> Result<Range<'_, K, V>, RangeError> {
+        match (range.start_bound(), range.end_bound()) {
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) if start > end => Err(RangeError::Inverted),
+            _ => Ok(self.range(range)),
+        }
+    }
+
+    /// Entries whose keys fall in `range`, from the largest down. Without the `doubly-linked`
+    /// feature each step searches for the predecessor again, O(log n) per entry; with it,
+    /// steps follow the level-0 back links in O(1).
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> RangeRev<'_, K, V, G> {
+        let front = Self::lower_bound_ptr(&self.head, range.start_bound());
+
+        let back_ptr = match past_upper_bound(range.end_bound()) {
+            Some(bound) => Self::get_bound_adjust_nodes(&self.head, bound).0,
+            None => Self::get_tail_nodes(&self.head).0,
+        };
+        let back = Some(unsafe { &*back_ptr.as_ptr() }).filter(|node| {
+            front.is_some()
+                && node
+                    .key()
+                    .is_some_and(|k| !before_lower_bound(k, range.start_bound()))
+        });
+
+        RangeRev {
+            list: self,
+            front,
+            back,
+        }
+    }
+
+    /// Removes every entry whose key falls outside `range`.
+    pub fn retain_range<R: RangeBounds<K>>(&mut self, range: R) {
+        if let Some(bound) = past_upper_bound(range.end_bound()) {
+            let (_, adjust_nodes) = Self::get_bound_adjust_nodes(&self.head, bound);
+            let (chain, _) = self.unlink_segment(&adjust_nodes, None);
+            drop_chain(chain);
+        }
+
+        if range.start_bound() != Bound::Unbounded {
+            let end = Self::lower_bound_ptr(&self.head, range.start_bound());
+            let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, 0);
+            let (chain, _) = self.unlink_segment(&adjust_nodes, end);
+            drop_chain(chain);
+        }
+    }
+
+    /// Removes every entry with a key `< watermark` and returns how many there were. The
+    /// head is relinked once per level to the first survivor, O(log n + removed).
+    pub fn evict_below(&mut self, watermark: &K) -> usize {
+        let end = Self::lower_bound_ptr(&self.head, Bound::Included(watermark));
+        let (_, adjust_nodes) = Self::get_index_adjust_nodes(&self.head, 0);
+        let (chain, count) = self.unlink_segment(&adjust_nodes, end);
+        drop_chain(chain);
+        count
+    }
+
+    /// Unlinks the entries in `range`. Returns the nodes before the gap, the detached level-0
+    /// chain and its length.
+    fn unlink_range<R: RangeBounds<K>>(
+        &mut self,
+        range: &R,
+    ) -> (AdjustNodes<K, V>, Chain<K, V>, usize) {
+        let (_, adjust_nodes) = Self::get_bound_adjust_nodes(&self.head, range.start_bound());
+        let end = past_upper_bound(range.end_bound())
+            .and_then(|bound| Self::lower_bound_ptr(&self.head, bound));
+
+        let (chain, count) = self.unlink_segment(&adjust_nodes, end);
+        (adjust_nodes, chain, count)
+    }
+
+    /// Removes the entries in `range` up front and yields them in key order. Whatever is
+    /// left unconsumed is dropped with the iterator.
+    pub fn drain_range<R: RangeBounds<K>>(&mut self, range: R) -> DrainRange<'_, K, V> {
+        let (_, chain, _) = self.unlink_range(&range);
+        DrainRange {
+            chain,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like `drain_range`, but collects the removed entries into a `Vec` sized up front.
+    pub fn remove_range_vec<R: RangeBounds<K>>(&mut self, range: R) -> Vec<(K, V)> {
+        let (_, chain, count) = self.unlink_range(&range);
+        let mut entries = Vec::with_capacity(count);
+        entries.extend(DrainRange {
+            chain,
+            _marker: PhantomData,
+        });
+        entries
+    }
+
+    /// Replaces the entries in `range` with `replacement`, whose keys must be strictly
+    /// increasing and inside `range`; debug builds panic otherwise.
+    pub fn replace_range<R, I>(&mut self, range: R, replacement: I)
+    where
+        R: RangeBounds<K>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let (mut adjust_nodes, chain, _) = self.unlink_range(&range);
+        drop_chain(chain);
+
+        for (key, value) in replacement {
+            debug_assert!(
+                range.contains(&key),
+                "replace_range: replacement key outside the range."
+            );
+            debug_assert!(
+                adjust_nodes[0]
+                    .is_none_or(|ptr| unsafe { ptr.as_ref().key() }.is_none_or(|k| *k < key)),
+                "replace_range: replacement keys must be strictly increasing."
+            );
+
+            let node_ptr = self.insert_at(adjust_nodes, key, value);
+            let level = unsafe { node_ptr.as_ref().level() };
+            for adjust_node in adjust_nodes.iter_mut().take(level) {
+                *adjust_node = Some(node_ptr);
+            }
+        }
+    }
+
+    /// Whether no key falls in `range`. One descent to the lower bound, O(log n).
+    pub fn range_is_empty<R: RangeBounds<K>>(&self, range: R) -> bool {
+        Self::lower_bound_ptr(&self.head, range.start_bound()).is_none_or(|ptr| {
+            unsafe { ptr.as_ref() }
+                .key()
+                .is_none_or(|k| !within_upper_bound(k, range.end_bound()))
+        })
+    }
+
+    /// Number of keys in `[lo, hi]`, 0 if `lo > hi`. Walks the window, so O(log n + count).
+    pub fn count_between(&self, lo: &K, hi: &K) -> usize {
+        if lo > hi {
+            return 0;
+        }
+        self.range(lo..=hi).count()
+    }
+
+    /// Number of keys `< key`. Nodes don't record spans, so this walks level 0 in O(count).
+    pub fn count_less_than(&self, key: &K) -> usize {
+        match self.search(key) {
+            Ok(index) | Err(index) => index,
+        }
+    }
+
+    /// Number of keys `> key`, also an O(count_less_than) level-0 walk.
+    pub fn count_greater_than(&self, key: &K) -> usize {
+        match self.search(key) {
+            Ok(index) => self.len - index - 1,
+            Err(index) => self.len - index,
+        }
+    }
+
+    /// Number of keys equal to `key`, 0 or 1 since keys are unique. O(log n).
+    pub fn count_equal(&self, key: &K) -> usize {
+        usize::from(self.get(key).is_some())
+    }
+
+    /// The smallest integer key `>= start` that is not in the list, e.g. the next free id.
+    /// Walks the run of consecutive keys from `start`, O(log n + run). `None` if the run
+    /// reaches the largest value of `K`.
+    pub fn first_gap(&self, start: K) -> Option<K>
+    where
+        K: Copy + Successor,
+    {
+        self.gap_from(start, None)
+    }
+
+    /// Like `first_gap`, but only looks below `end`, `None` if `[start, end)` is full.
+    pub fn first_gap_in_range(&self, start: K, end: K) -> Option<K>
+    where
+        K: Copy + Successor,
+    {
+        self.gap_from(start, Some(end))
+    }
+
+    /// The first key missing from the run of consecutive keys from `start`. Stops at `end`
+    /// before comparing, and at the largest key before stepping past it.
+    fn gap_from(&self, start: K, end: Option<K>) -> Option<K>
+    where
+        K: Copy + Successor,
+    {
+        let mut candidate = start;
+        for (key, _) in self.iter_from(&start) {
+            if *key != candidate || end.is_some_and(|end| candidate >= end) {
+                break;
+            }
+            candidate = candidate.successor()?;
+        }
+        Some(candidate).filter(|candidate| end.is_none_or(|end| *candidate < end))
+    }
+
+    /// Sum of the values in `range`, `V::default()` if it is empty.
+    pub fn sum_range<R: RangeBounds<K>>(&self, range: R) -> V
+    where
+        V: Default + Add<Output = V> + Copy,
+    {
+        self.range(range)
+            .fold(V::default(), |acc, (_, value)| acc + *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::collections::skip_list::{RangeError, SkipList};
+
+    #[test]
+    fn range() {
+        let mut sl = SkipList::new();
+
+        for i in 0..100 {
+            sl.insert(i * 2, i);
+        }
+
+        let keys: Vec<_> = sl.range(10..20).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![10, 12, 14, 16, 18]);
+
+        let keys: Vec<_> = sl.range(11..=20).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![12, 14, 16, 18, 20]);
+
+        let keys: Vec<_> = sl.range(..4).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![0, 2]);
+
+        let keys: Vec<_> = sl.range(195..).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![196, 198]);
+
+        assert_eq!(sl.range(..).count(), 100);
+        assert_eq!(
+            sl.range((Bound::Included(20), Bound::Excluded(10))).count(),
+            0
+        );
+        assert_eq!(sl.range(11..12).count(), 0);
+        assert_eq!(sl.range(500..).count(), 0);
+    }
+
+    #[test]
+    fn range_mut() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i * 2, i);
+        }
+
+        for (k, v) in sl.range_mut(10..20) {
+            *v = *k * 100;
+        }
+        assert_eq!(sl.get(&8), Some(&4));
+        assert_eq!(sl.get(&10), Some(&1000));
+        assert_eq!(sl.get(&18), Some(&1800));
+        assert_eq!(sl.get(&20), Some(&10));
+
+        assert_eq!(sl.range_mut(..).count(), 100);
+        assert_eq!(sl.range_mut(11..12).count(), 0);
+        assert_eq!(
+            sl.range_mut((Bound::Included(20), Bound::Excluded(10)))
+                .count(),
+            0
+        );
+        assert_eq!(sl.range_mut(195..).count(), 2);
+    }
+
+    #[test]
+    fn range_values() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            sl.insert(i, i * 10);
+        }
+
+        assert!(sl.range_values(2..5).eq(&[20, 30, 40]));
+        for value in sl.range_values_mut(8..) {
+            *value += 1;
+        }
+        assert!(sl.range_values(7..).eq(&[70, 81, 91]));
+    }
+
+    #[test]
+    fn map_range() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            sl.insert(i, 100);
+        }
+
+        sl.map_range(3..6, |k, v| *v /= k);
+        assert!(sl.range_values(2..7).eq(&[100, 33, 25, 20, 100]));
+
+        sl.map_range(8.., |_, v| *v = 0);
+        sl.map_range(..1, |_, v| *v = 1);
+        assert!(sl
+            .range_values(..)
+            .eq(&[1, 100, 100, 33, 25, 20, 100, 100, 0, 0]));
+    }
+
+    #[test]
+    fn shrink_range_values() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            let mut v = Vec::with_capacity(64);
+            v.push(i);
+            sl.insert(i, v);
+        }
+
+        sl.shrink_range_values(3..=5, Vec::shrink_to_fit);
+        let capacities: Vec<_> = sl.range_values(2..7).map(Vec::capacity).collect();
+        assert_eq!(capacities, vec![64, 1, 1, 1, 64]);
+        assert!(sl.iter().all(|(k, v)| v == &[*k]));
+    }
+
+    #[test]
+    fn try_range() {
+        let mut sl = SkipList::new();
+        for i in 0..10 {
+            sl.insert(i, i);
+        }
+
+        let keys: Vec<_> = sl.try_range(2..5).unwrap().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![2, 3, 4]);
+        assert_eq!(sl.try_range(5..5).unwrap().count(), 0);
+        assert_eq!(sl.try_range(..).unwrap().count(), 10);
+        assert_eq!(
+            sl.try_range((Bound::Included(5), Bound::Excluded(2))).err(),
+            Some(RangeError::Inverted)
+        );
+    }
+
+    #[test]
+    fn range_rev() {
+        let mut sl = SkipList::new();
+
+        for i in 0..100 {
+            sl.insert(i * 2, i);
+        }
+
+        let keys: Vec<_> = sl.range_rev(10..20).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![18, 16, 14, 12, 10]);
+
+        let keys: Vec<_> = sl.range_rev(11..=20).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![20, 18, 16, 14, 12]);
+
+        let keys: Vec<_> = sl.range_rev(..4).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![2, 0]);
+
+        let keys: Vec<_> = sl.range_rev(195..).map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec![198, 196]);
+
+        assert!(sl
+            .range_rev(..)
+            .map(|(k, _)| *k)
+            .eq((0..100).rev().map(|i| i * 2)));
+        assert_eq!(
+            sl.range_rev((Bound::Included(20), Bound::Excluded(10)))
+                .count(),
+            0
+        );
+        assert_eq!(sl.range_rev(11..12).count(), 0);
+        assert_eq!(sl.range_rev(500..).count(), 0);
+        assert_eq!(sl.range_rev(..-1).count(), 0);
+    }
+
+    #[test]
+    fn retain_range() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i, i);
+        }
+
+        sl.retain_range(20..80);
+        assert_eq!(sl.len(), 60);
+        assert_eq!(sl.first_key(), Some(&20));
+        assert_eq!(sl.last_key(), Some(&79));
+        assert_eq!(sl.get(&19), None);
+        assert_eq!(sl.get(&80), None);
+
+        sl.retain_range(..=50);
+        assert_eq!(sl.len(), 31);
+        assert_eq!(sl.last_key(), Some(&50));
+
+        sl.retain_range(..);
+        assert_eq!(sl.len(), 31);
+
+        sl.insert(10, 10);
+        sl.insert(90, 90);
+        assert_eq!(sl.iter().count(), 33);
+
+        sl.retain_range(60..);
+        assert_eq!(sl.len(), 1);
+        assert_eq!(sl.first_key(), Some(&90));
+
+        sl.retain_range((Bound::Included(5), Bound::Excluded(0)));
+        assert!(sl.is_empty());
+    }
+
+    #[test]
+    fn drain_range() {
+        let mut sl = SkipList::from_sorted((0..100).map(|i| (i, i.to_string())));
+
+        let drained: Vec<_> = sl.drain_range(10..15).collect();
+        assert_eq!(
+            drained,
+            (10..15).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+        );
+        assert_eq!(sl.len(), 95);
+        assert_eq!(sl.get(&12), None);
+
+        let mut drain = sl.drain_range(50..);
+        assert_eq!(drain.next(), Some((50, "50".to_string())));
+        drop(drain);
+        assert_eq!(sl.len(), 45);
+        assert_eq!(sl.last_key(), Some(&49));
+
+        assert_eq!(sl.drain_range(200..).count(), 0);
+        sl.insert(12, "12".to_string());
+        assert_eq!(sl.range(9..16).count(), 3);
+    }
+
+    #[test]
+    fn remove_range_vec() {
+        let mut sl = SkipList::from_sorted((0..100).map(|i| (i, i.to_string())));
+
+        let removed = sl.remove_range_vec(10..=14);
+        assert_eq!(
+            removed,
+            (10..15).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+        );
+        assert_eq!(removed.capacity(), 5);
+        assert_eq!(sl.len(), 95);
+        assert_eq!(sl.range(9..16).count(), 2);
+
+        assert_eq!(sl.remove_range_vec(90..).len(), 10);
+        assert_eq!(sl.remove_range_vec(200..), vec![]);
+        assert_eq!(sl.len(), 85);
+        assert_eq!(sl.last_key(), Some(&89));
+        sl.assert_valid();
+    }
+
+    #[test]
+    fn replace_range() {
+        let mut sl = SkipList::from_sorted((0..100).map(|i| (i, i)));
+
+        sl.replace_range(10..20, [(11, -11), (15, -15)]);
+        assert_eq!(sl.len(), 92);
+        assert_eq!(
+            sl.range(8..22).map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![(8, 8), (9, 9), (11, -11), (15, -15), (20, 20), (21, 21)]
+        );
+
+        sl.replace_range(50.., (50..60).map(|i| (i, 0)));
+        assert_eq!(sl.len(), 52);
+        assert_eq!(sl.last_key(), Some(&59));
+
+        sl.replace_range(..5, []);
+        assert_eq!(sl.first_key(), Some(&5));
+        assert_eq!(sl.len(), 47);
+
+        sl.replace_range((Bound::Included(40), Bound::Excluded(30)), []);
+        assert_eq!(sl.len(), 47);
+
+        for i in 0..200 {
+            sl.insert(i, i);
+        }
+        assert!(sl.iter().map(|(k, _)| *k).eq(0..200));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "replace_range")]
+    fn replace_range_outside() {
+        let mut sl = SkipList::from_sorted((0..10).map(|i| (i, i)));
+        sl.replace_range(2..4, [(5, 5)]);
+    }
+
+    #[test]
+    fn range_is_empty() {
+        let mut sl = SkipList::new();
+        for i in 0..50 {
+            sl.insert(i * 2, i);
+        }
+
+        assert!(!sl.range_is_empty(10..11));
+        assert!(sl.range_is_empty(11..12));
+        assert!(!sl.range_is_empty(11..=12));
+        assert!(sl.range_is_empty(100..));
+        assert!(!sl.range_is_empty(98..));
+        assert!(sl.range_is_empty(..0));
+        assert!(!sl.range_is_empty(..));
+        assert!(sl.range_is_empty((Bound::Included(20), Bound::Excluded(10))));
+        assert!(SkipList::<i32, i32>::new().range_is_empty(..));
+    }
+
+    #[test]
+    fn first_gap() {
+        let mut sl = SkipList::new();
+        for k in [0u32, 1, 2, 4, 5, 7] {
+            sl.insert(k, ());
+        }
+
+        assert_eq!(sl.first_gap(0), Some(3));
+        assert_eq!(sl.first_gap(3), Some(3));
+        assert_eq!(sl.first_gap(4), Some(6));
+        assert_eq!(sl.first_gap(7), Some(8));
+        assert_eq!(sl.first_gap(100), Some(100));
+
+        assert_eq!(sl.first_gap_in_range(0, 10), Some(3));
+        assert_eq!(sl.first_gap_in_range(0, 3), None);
+        assert_eq!(sl.first_gap_in_range(4, 6), None);
+        assert_eq!(sl.first_gap_in_range(4, 7), Some(6));
+        assert_eq!(sl.first_gap_in_range(5, 5), None);
+    }
+
+    #[test]
+    fn first_gap_at_max() {
+        let mut sl = SkipList::new();
+        for k in 250u8..=255 {
+            sl.insert(k, ());
+        }
+
+        assert_eq!(sl.first_gap(250), None);
+        assert_eq!(sl.first_gap(255), None);
+        assert_eq!(sl.first_gap(0), Some(0));
+        assert_eq!(sl.first_gap_in_range(250, 255), None);
+
+        sl.remove(&253);
+        assert_eq!(sl.first_gap(250), Some(253));
+        assert_eq!(sl.first_gap(254), None);
+    }
+
+    #[test]
+    fn evict_below() {
+        let mut sl = SkipList::new();
+        for i in 0..100 {
+            sl.insert(i * 2, i);
+        }
+
+        assert_eq!(sl.evict_below(&-1), 0);
+        assert_eq!(sl.evict_below(&0), 0);
+        assert_eq!(sl.evict_below(&11), 6);
+        assert_eq!(sl.first_key(), Some(&12));
+        assert_eq!(sl.evict_below(&12), 0);
+        assert_eq!(sl.len(), 94);
+        sl.assert_valid();
+
+        assert_eq!(sl.evict_below(&1000), 94);
+        assert!(sl.is_empty());
+        sl.assert_valid();
+        sl.insert(0, 0);
+        assert_eq!(sl.get(&0), Some(&0));
+    }
+
+    #[test]
+    fn count_between() {
+        let mut sl = SkipList::new();
+
+        for i in 0..50 {
+            sl.insert(i * 2, i);
+        }
+
+        assert_eq!(sl.count_between(&10, &20), 6);
+        assert_eq!(sl.count_between(&11, &19), 4);
+        assert_eq!(sl.count_between(&10, &10), 1);
+        assert_eq!(sl.count_between(&11, &11), 0);
+        assert_eq!(sl.count_between(&20, &10), 0);
+        assert_eq!(sl.count_between(&-5, &500), 50);
+    }
+
+    #[test]
+    fn count_around_key() {
+        let mut sl = SkipList::new();
+
+        for i in 0..50 {
+            sl.insert(i * 2, i);
+        }
+
+        assert_eq!(sl.count_less_than(&10), 5);
+        assert_eq!(sl.count_greater_than(&10), 44);
+        assert_eq!(sl.count_equal(&10), 1);
+
+        assert_eq!(sl.count_less_than(&11), 6);
+        assert_eq!(sl.count_greater_than(&11), 44);
+        assert_eq!(sl.count_equal(&11), 0);
+
+        assert_eq!(sl.count_less_than(&-1), 0);
+        assert_eq!(sl.count_greater_than(&-1), 50);
+        assert_eq!(sl.count_less_than(&500), 50);
+        assert_eq!(sl.count_greater_than(&500), 0);
+    }
+
+    #[test]
+    fn sum_range() {
+        let mut sl = SkipList::new();
+
+        for i in 0..10 {
+            sl.insert(i, i);
+        }
+
+        assert_eq!(sl.sum_range(2..5), 2 + 3 + 4);
+        assert_eq!(sl.sum_range(..), 45);
+        assert_eq!(sl.sum_range(20..), 0);
+    }
+}
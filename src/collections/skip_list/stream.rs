@@ -0,0 +1,96 @@
+use std::{
+    io::{self, Read, Write},
+    ptr::NonNull,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{gen_level::LevelGenerator, DefaultGenerator, SkipList, MAX_LEVEL};
+
+fn into_io_error(err: bincode::ErrorKind) -> io::Error {
+    match err {
+        bincode::ErrorKind::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+impl<K, V, G> SkipList<K, V, G>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    G: LevelGenerator,
+{
+    /// Writes a `u64` little-endian length followed by each pair in key order.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&(self.len() as u64).to_le_bytes())?;
+        for pair in self.iter() {
+            bincode::serialize_into(&mut *w, &pair).map_err(|err| into_io_error(*err))?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V> SkipList<K, V, DefaultGenerator>
+where
+    K: Ord + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    /// Reads a stream produced by `write_to`, appending pairs as in `from_sorted`.
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut len = [0; 8];
+        r.read_exact(&mut len)?;
+        let len = u64::from_le_bytes(len);
+
+        let mut sl = Self::new();
+        let mut tails = [Some(NonNull::from(&sl.head)); MAX_LEVEL];
+        for _ in 0..len {
+            let (key, value): (K, V) =
+                bincode::deserialize_from(&mut *r).map_err(|err| into_io_error(*err))?;
+
+            let last_ptr = tails[0].expect("must have a tail node.");
+            if unsafe { last_ptr.as_ref().key() }.is_some_and(|k| *k >= key) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "keys are not in strictly increasing order.",
+                ));
+            }
+
+            sl.push_back(&mut tails, key, value);
+        }
+
+        Ok(sl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::collections::skip_list::SkipList;
+
+    #[test]
+    fn write_and_read() {
+        let sl = SkipList::from_sorted((0..1000u32).map(|i| (i, i.to_string())));
+
+        let mut buf = Vec::new();
+        sl.write_to(&mut buf).unwrap();
+
+        let read = SkipList::<u32, String>::read_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(read.len(), 1000);
+        assert!(read.iter().eq(sl.iter()));
+    }
+
+    #[test]
+    fn read_truncated() {
+        let sl = SkipList::from_sorted((0..10u32).map(|i| (i, i)));
+
+        let mut buf = Vec::new();
+        sl.write_to(&mut buf).unwrap();
+        buf.truncate(buf.len() - 1);
+
+        let err = SkipList::<u32, u32>::read_from(&mut buf.as_slice())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}